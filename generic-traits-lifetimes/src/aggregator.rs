@@ -0,0 +1,289 @@
+// The Summary traits and the types implementing them live in their own module so main.rs can bring them into scope
+// with `use`, just like an external crate called aggregator would be used.
+
+use std::collections::{HashMap, HashSet};
+
+// Traits
+// Traits are a way to define behaviour in a generic way. They are similiar to interfaces in other languages.
+// Example of a trait.
+pub trait Summary {
+    fn summarize(&self) -> String;
+}
+// Here, we declare a trait using the trait keyword. We the define a method and instead providing an implementation, we use a semicolon.
+// Now here this trait means that the compiler will enforce that any type that implements this trait will have a method called summarize, defined within
+// this trait.
+// A trait can have multiple methods in it.
+
+// We can also do default implementations for traits.
+pub trait Summary2 {
+    fn summarize_author(&self) -> String;
+
+    fn summarize(&self) -> String {
+        format!("(Read more from {}...)", self.summarize_author())
+    }
+}
+// Now, any type that implements the Summary2 trait will have a default implementation of the summarize method.
+// If we want to override the default implementation, we can do so by providing an implementation of the summarize method for that type.
+// Default implementations can call other methods in the same trait, even if those other methods do not have a default implementation.
+// In this way, a trait can provide a lot of useful functionality and only require implementors to specify a small part of it.
+
+// Implementing a trait on a type.
+pub struct NewsArticle {
+    pub headline: String,
+    pub location: String,
+    pub author: String,
+    pub content: String,
+}
+
+impl Summary for NewsArticle {
+    fn summarize(&self) -> String {
+        format!("{}, by {} ({})", self.headline, self.author, self.location)
+    }
+}
+
+pub type TweetId = u64;
+
+// Instead of plain reply/retweet flags, a tweet points at the tweet it answers or shares by id.
+// A tweet with reply_to set is a reply, a tweet with retweet_of set is a retweet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tweet {
+    pub id: TweetId,
+    pub username: String,
+    pub content: String,
+    pub reply_to: Option<TweetId>,
+    pub retweet_of: Option<TweetId>,
+}
+
+impl Tweet {
+    pub fn new(id: TweetId, username: &str, content: &str) -> Tweet {
+        Tweet {
+            id,
+            username: String::from(username),
+            content: String::from(content),
+            reply_to: None,
+            retweet_of: None,
+        }
+    }
+
+    pub fn reply(id: TweetId, username: &str, content: &str, parent: TweetId) -> Tweet {
+        Tweet {
+            reply_to: Some(parent),
+            ..Tweet::new(id, username, content)
+        }
+    }
+
+    pub fn retweet(id: TweetId, username: &str, original: TweetId) -> Tweet {
+        Tweet {
+            retweet_of: Some(original),
+            ..Tweet::new(id, username, "")
+        }
+    }
+}
+
+// impl Summary for Tweet {
+//     fn summarize(&self) -> String {
+//         format!("{}: {}", self.username, self.content)
+//     }
+// }
+
+impl Summary2 for Tweet {
+    fn summarize_author(&self) -> String {
+        format!("@{}", self.username)
+    }
+}
+// It is not possible to call the default implementation from an overriding implementation of the same method.
+
+// Implementing a trait on a type is similiar to implementing regular methods. The difference is that after impl we put the trait name we want to
+// implement, then use the for keyword, and then specify the name of the type we want to implement the trait for.
+
+// A reply tree rooted at one tweet. Replies are kept in the order they were added to the store.
+#[derive(Debug, PartialEq)]
+pub struct ThreadNode {
+    pub id: TweetId,
+    pub replies: Vec<ThreadNode>,
+}
+
+impl ThreadNode {
+    pub fn len(&self) -> usize {
+        1 + self.replies.iter().map(ThreadNode::len).sum::<usize>()
+    }
+}
+
+// Stores tweets by id and remembers which tweets reply to which, so conversations can be rebuilt.
+// Parents are only referenced by id, so a broken or malicious chain (a replies to b, b replies to a) is possible.
+// Every traversal keeps a set of visited ids and stops instead of looping forever.
+#[derive(Debug, Default)]
+pub struct ThreadStore {
+    tweets: HashMap<TweetId, Tweet>,
+    replies: HashMap<TweetId, Vec<TweetId>>,
+}
+
+impl ThreadStore {
+    pub fn new() -> ThreadStore {
+        ThreadStore::default()
+    }
+
+    // Adds a tweet, returning the tweet it replaced if the id was already taken.
+    pub fn insert(&mut self, tweet: Tweet) -> Option<Tweet> {
+        let previous = self.tweets.remove(&tweet.id);
+        if let Some(parent) = previous.as_ref().and_then(|old| old.reply_to) {
+            if let Some(siblings) = self.replies.get_mut(&parent) {
+                siblings.retain(|&id| id != tweet.id);
+            }
+        }
+
+        if let Some(parent) = tweet.reply_to {
+            self.replies.entry(parent).or_default().push(tweet.id);
+        }
+        self.tweets.insert(tweet.id, tweet);
+        previous
+    }
+
+    // Ids of the stored tweets that reply directly to `id`.
+    pub fn replies_to(&self, id: TweetId) -> &[TweetId] {
+        self.replies.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    // The chain of stored tweets `id` replies to, nearest parent first.
+    // The walk stops at a tweet that is not a reply, at a parent missing from the store, or when it would revisit a tweet.
+    pub fn ancestors(&self, id: TweetId) -> Vec<TweetId> {
+        let mut chain = Vec::new();
+        let mut seen = HashSet::from([id]);
+        let mut current = self.tweets.get(&id).and_then(|tweet| tweet.reply_to);
+
+        while let Some(parent) = current {
+            if !self.tweets.contains_key(&parent) || !seen.insert(parent) {
+                break;
+            }
+            chain.push(parent);
+            current = self.tweets[&parent].reply_to;
+        }
+
+        chain
+    }
+
+    // The tweet that started the conversation `id` belongs to.
+    pub fn root(&self, id: TweetId) -> Option<TweetId> {
+        if !self.tweets.contains_key(&id) {
+            return None;
+        }
+        Some(self.ancestors(id).last().copied().unwrap_or(id))
+    }
+
+    // Rebuilds the reply tree below `root`. A tweet is only placed once, even if the reply links loop back.
+    pub fn thread(&self, root: TweetId) -> Option<ThreadNode> {
+        if !self.tweets.contains_key(&root) {
+            return None;
+        }
+        let mut seen = HashSet::new();
+        Some(self.build_node(root, &mut seen))
+    }
+
+    // The whole conversation `id` is part of, starting from its root.
+    pub fn conversation(&self, id: TweetId) -> Option<ThreadNode> {
+        self.root(id).and_then(|root| self.thread(root))
+    }
+
+    fn build_node(&self, id: TweetId, seen: &mut HashSet<TweetId>) -> ThreadNode {
+        seen.insert(id);
+        let mut replies = Vec::new();
+        for &reply in self.replies_to(id) {
+            if self.tweets.contains_key(&reply) && !seen.contains(&reply) {
+                replies.push(self.build_node(reply, seen));
+            }
+        }
+        ThreadNode { id, replies }
+    }
+
+    // A one line summary including who the tweet answers or shares, e.g. "@a replying to @b: ...".
+    pub fn summarize(&self, id: TweetId) -> Option<String> {
+        let tweet = self.tweets.get(&id)?;
+        let author = tweet.summarize_author();
+
+        let summary = if let Some(original) = tweet.retweet_of {
+            match self.tweets.get(&original) {
+                Some(original) => format!(
+                    "{} retweeted {}: {}",
+                    author,
+                    original.summarize_author(),
+                    original.content
+                ),
+                None => format!("{} retweeted an unavailable tweet", author),
+            }
+        } else if let Some(parent) = tweet.reply_to {
+            match self.tweets.get(&parent) {
+                Some(parent) => format!(
+                    "{} replying to {}: {}",
+                    author,
+                    parent.summarize_author(),
+                    tweet.content
+                ),
+                None => format!("{} replying to an unavailable tweet: {}", author, tweet.content),
+            }
+        } else {
+            format!("{}: {}", author, tweet.content)
+        };
+
+        Some(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_store() -> ThreadStore {
+        let mut store = ThreadStore::new();
+        store.insert(Tweet::new(1, "a", "hello"));
+        store.insert(Tweet::reply(2, "b", "hi a", 1));
+        store.insert(Tweet::reply(3, "c", "hi b", 2));
+        store.insert(Tweet::reply(4, "d", "also hi a", 1));
+        store.insert(Tweet::retweet(5, "e", 1));
+        store
+    }
+
+    #[test]
+    fn summaries_include_reply_and_retweet_context() {
+        let store = sample_store();
+
+        assert_eq!(store.summarize(1).unwrap(), "@a: hello");
+        assert_eq!(store.summarize(2).unwrap(), "@b replying to @a: hi a");
+        assert_eq!(store.summarize(5).unwrap(), "@e retweeted @a: hello");
+        assert_eq!(store.summarize(42), None);
+    }
+
+    #[test]
+    fn rebuilds_conversation_from_any_tweet() {
+        let store = sample_store();
+
+        assert_eq!(store.ancestors(3), vec![2, 1]);
+        assert_eq!(store.root(3), Some(1));
+
+        let thread = store.conversation(3).unwrap();
+        assert_eq!(thread.id, 1);
+        assert_eq!(thread.len(), 4);
+        assert_eq!(thread.replies[0].replies[0].id, 3);
+        assert_eq!(thread.replies[1].id, 4);
+    }
+
+    #[test]
+    fn reply_cycles_do_not_loop_forever() {
+        let mut store = ThreadStore::new();
+        store.insert(Tweet::reply(1, "a", "first", 2));
+        store.insert(Tweet::reply(2, "b", "second", 1));
+
+        assert_eq!(store.ancestors(1), vec![2]);
+        assert_eq!(store.root(1), Some(2));
+        assert_eq!(store.thread(1).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn replacing_a_tweet_moves_it_between_threads() {
+        let mut store = sample_store();
+        let old = store.insert(Tweet::reply(4, "d", "actually replying to b", 2));
+
+        assert_eq!(old.unwrap().reply_to, Some(1));
+        assert_eq!(store.replies_to(1), &[2]);
+        assert_eq!(store.replies_to(2), &[3, 4]);
+    }
+}
@@ -13,6 +13,10 @@
 use std::fmt::Display;
 use std::fmt::Debug;
 
+use aggregator::{NewsArticle, Summary, Summary2, ThreadStore, Tweet};
+
+mod aggregator;
+
 fn largest_i32(list: &[i32]) -> &i32 {
     let mut largest = &list[0];
 
@@ -171,77 +175,38 @@ fn main() {
     println!("p3.x = {}, p3.y = {}", p3.x, p3.y);
 
     // Traits
-    // Traits are a way to define behaviour in a generic way. They are similiar to interfaces in other languages.
-    // Example of a trait.
-
-    pub trait Summary {
-        fn summarize(&self) -> String;
-    }
-
-    // We can also do default implementations for traits.
-    pub trait Summary2 {
-        fn summarize_author(&self) -> String;
-
-        fn summarize(&self) -> String {
-            format!("(Read more from {}...)", self.summarize_author())
-        }
-    }
-    // Now, any type that implements the Summary2 trait will have a default implementation of the summarize method.
-    // If we want to override the default implementation, we can do so by providing an implementation of the summarize method for that type.
-    // Default implementations can call other methods in the same trait, even if those other methods do not have a default implementation.
-    // In this way, a trait can provide a lot of useful functionality and only require implementors to specify a small part of it.
-
-    // Here, we declare a trait using the trait keyword. We the define a method and instead providing an implementation, we use a semicolon.
-    // Now here this trait means that the compiler will enforce that any type that implements this trait will have a method called summarize, defined within
-    // this trait.
-    // A trait can have multiple methods in it.
-
-    // Implementing a trait on a type.
-    pub struct NewsArticle {
-        pub headline: String,
-        pub location: String,
-        pub author: String,
-        pub content: String,
-    }
-
-    impl Summary for NewsArticle {
-        fn summarize(&self) -> String {
-            format!("{}, by {} ({})", self.headline, self.author, self.location)
-        }
-    }
+    // The Summary traits, NewsArticle and Tweet are defined in src/aggregator.rs and brought into scope at the top of this file.
 
-    pub struct Tweet {
-        pub username: String,
-        pub content: String,
-        pub reply: bool,
-        pub retweet: bool,
-    }
-
-    // impl Summary for Tweet {
-    //     fn summarize(&self) -> String {
-    //         format!("{}: {}", self.username, self.content)
-    //     }
-    // }
-
-    impl Summary2 for Tweet {
-        fn summarize_author(&self) -> String {
-            format!("@{}", self.username)
-        }
-    }
+    let tweet = Tweet::new(
+        1,
+        "horse_ebooks",
+        "of course, as you probably already know, people",
+    );
+    println!("1 new tweet: {}", tweet.summarize());
 
-    let tweet = Tweet {
-        username: String::from("horse_ebooks"),
+    let article = NewsArticle {
+        headline: String::from("Penguins win the Stanley Cup Championship!"),
+        location: String::from("Pittsburgh, PA, USA"),
+        author: String::from("Iceburgh"),
         content: String::from(
-            "of course, as you probably already know, people",
+            "The Pittsburgh Penguins once again are the best hockey team in the NHL.",
         ),
-        reply: false,
-        retweet: false,
     };
-    println!("1 new tweet: {}", tweet.summarize());
-    // It is not possible to call the default implementation from an overriding implementation of the same method.
+    println!("New article available! {}", article.summarize());
+    println!("{}", article.content);
 
-    // Implementing a trait on a type is similiar to implementing regular methods. The difference is that after impl we put the trait name we want to 
-    // implement, then use the for keyword, and then specify the name of the type we want to implement the trait for.
+    // Tweets can point at the tweet they reply to or retweet, and a ThreadStore rebuilds the conversation from those links.
+    let mut store = ThreadStore::new();
+    store.insert(tweet);
+    store.insert(Tweet::reply(2, "ferris", "people what?", 1));
+    store.insert(Tweet::retweet(3, "rustlang", 1));
+
+    for id in [1, 2, 3] {
+        println!("{}", store.summarize(id).unwrap());
+    }
+    if let Some(thread) = store.conversation(2) {
+        println!("The conversation has {} tweets", thread.len());
+    }
 
     // Traits as parameters.
     // We can also use traits as parameters in functions, which allows us to write functions that accept any type that implements a particular trait.
@@ -287,14 +252,11 @@ fn main() {
     // Returning types that implement traits.
     // We can also use the impl Trait syntax in the return position to return a value of some type that implements a trait.
     fn returns_summarizable() -> impl Summary2 {
-        Tweet {
-            username: String::from("horse_ebooks"),
-            content: String::from(
-                "of course, as you probably already know, people",
-            ),
-            reply: false,
-            retweet: false,
-        }
+        Tweet::new(
+            1,
+            "horse_ebooks",
+            "of course, as you probably already know, people",
+        )
     }
 
     // Using trait bounds to conditionally implement methods.
@@ -496,14 +458,11 @@ fn main() {
 use aggregator::{Summary, Tweet};
 
 fn main() {
-    let tweet = Tweet {
-        username: String::from("horse_ebooks"),
-        content: String::from(
-            "of course, as you probably already know, people",
-        ),
-        reply: false,
-        retweet: false,
-    };
+    let tweet = Tweet::new(
+        1,
+        "horse_ebooks",
+        "of course, as you probably already know, people",
+    );
 
     println!("1 new tweet: {}", tweet.summarize());
 }
@@ -0,0 +1,139 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt::Write;
+
+use crate::difficulty::GameRange;
+use crate::solver::{self, Strategy};
+
+// How one strategy did over many games on one range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub strategy: Strategy,
+    pub range: GameRange,
+    pub games: u32,
+    pub mean_guesses: f64,
+    pub max_guesses: u32,
+    // The strategy was not played because the range is too big for it, see MAX_SLOW_RANGE.
+    pub skipped: bool,
+}
+
+// Linear, and Random at worst, can need a guess for every number in the range. Above this many numbers
+// a bench of them would take minutes, so they are skipped.
+pub const MAX_SLOW_RANGE: i64 = 1_000_000;
+
+fn too_slow(strategy: Strategy, range: GameRange) -> bool {
+    matches!(strategy, Strategy::Linear | Strategy::Random) && range.size() > MAX_SLOW_RANGE
+}
+
+// Plays `games` games with secrets drawn from `seed`, so every strategy faces the same secrets for the same seed.
+pub fn run(strategy: Strategy, range: GameRange, games: u32, seed: u64) -> BenchResult {
+    if too_slow(strategy, range) {
+        return BenchResult {
+            strategy,
+            range,
+            games: 0,
+            mean_guesses: 0.0,
+            max_guesses: 0,
+            skipped: true,
+        };
+    }
+
+    let mut secrets = StdRng::seed_from_u64(seed);
+    let mut total: u64 = 0;
+    let mut max_guesses = 0;
+
+    for game in 0..games {
+        let secret = secrets.gen_range(range.low()..=range.high());
+        let guesses = solver::solve(strategy, range, secret, seed.wrapping_add(u64::from(game)));
+        total += u64::from(guesses);
+        max_guesses = max_guesses.max(guesses);
+    }
+
+    BenchResult {
+        strategy,
+        range,
+        games,
+        mean_guesses: if games == 0 { 0.0 } else { total as f64 / f64::from(games) },
+        max_guesses,
+        skipped: false,
+    }
+}
+
+// Runs each of `strategies` on every range.
+pub fn run_all(ranges: &[GameRange], strategies: &[Strategy], games: u32, seed: u64) -> Vec<BenchResult> {
+    let mut results = Vec::new();
+    for &range in ranges {
+        for &strategy in strategies {
+            results.push(run(strategy, range, games, seed));
+        }
+    }
+    results
+}
+
+pub fn report(results: &[BenchResult]) -> String {
+    let mut table = format!(
+        "{:<10} {:>12} {:>8} {:>8} {:>6}\n",
+        "strategy", "range size", "games", "mean", "max"
+    );
+    for result in results {
+        // Writing to a String cannot fail.
+        if result.skipped {
+            writeln!(
+                table,
+                "{:<10} {:>12}  skipped, the range is over {} numbers",
+                result.strategy,
+                result.range.size(),
+                MAX_SLOW_RANGE
+            )
+            .unwrap();
+            continue;
+        }
+        writeln!(
+            table,
+            "{:<10} {:>12} {:>8} {:>8.2} {:>6}",
+            result.strategy,
+            result.range.size(),
+            result.games,
+            result.mean_guesses,
+            result.max_guesses
+        )
+        .unwrap();
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_beats_linear() {
+        let range = GameRange::new(1, 1000).unwrap();
+        let binary = run(Strategy::Binary, range, 500, 3);
+        let linear = run(Strategy::Linear, range, 500, 3);
+
+        assert!(binary.max_guesses <= 10);
+        assert!(binary.mean_guesses < linear.mean_guesses);
+    }
+
+    #[test]
+    fn same_seed_gives_same_results() {
+        let ranges = [GameRange::default()];
+        let all = Strategy::ALL;
+        assert_eq!(run_all(&ranges, &all, 200, 9), run_all(&ranges, &all, 200, 9));
+        assert_eq!(report(&run_all(&ranges, &all, 10, 9)).lines().count(), 5);
+    }
+
+    #[test]
+    fn slow_strategies_skip_huge_ranges() {
+        let huge = GameRange::new(-2_000_000_000, 2_000_000_000).unwrap();
+        let results = run_all(&[huge], &Strategy::ALL, 10, 1);
+        let skipped: Vec<_> = results.iter().filter(|result| result.skipped).map(|result| result.strategy).collect();
+
+        assert_eq!(skipped, vec![Strategy::Random, Strategy::Linear]);
+        assert!(results[0].max_guesses <= 33);
+        let table = report(&results);
+        assert!(table.lines().last().unwrap().starts_with("linear"));
+        assert!(table.lines().last().unwrap().ends_with("4000000001  skipped, the range is over 1000000 numbers"));
+    }
+}
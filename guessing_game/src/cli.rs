@@ -0,0 +1,267 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use crate::difficulty::{Difficulty, GameRange};
+use crate::leaderboard;
+use crate::solver::Strategy;
+
+pub const USAGE: &str = "\
+Usage: guessing_game [OPTIONS]
+
+Options:
+  --difficulty <easy|medium|hard>  Preset range and number of attempts (default: medium)
+  --min <N>                        Lowest possible secret number, overrides the preset
+  --max <N>                        Highest possible secret number, overrides the preset
+  --attempts <N>                   Number of guesses allowed, overrides the preset
+  --seed <N>                       Pick the secret number reproducibly from this seed
+  --bench <GAMES>                  Let every solver strategy play GAMES games per range and compare them
+  --strategy <NAME>                Only bench this strategy: binary, skewed, random or linear
+  --player <NAME>                  Name to record wins under on the leaderboard (default: anonymous)
+  --leaderboard <PATH>             Leaderboard file (default: leaderboard.tsv)
+  --stats                          Print the leaderboard instead of playing
+  --reverse                        Think of a number yourself and let the computer guess it
+  --serve <ADDR>                   Host a game on ADDR (e.g. 127.0.0.1:7878), you pick the secret number
+  --players <N>                    How many players the host waits for (default: 2)
+  --connect <ADDR>                 Join a game hosted on ADDR";
+
+// What the program should do once the arguments are parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Play,
+    // None benches every strategy.
+    Bench { games: u32, strategy: Option<Strategy> },
+    Stats,
+    Reverse,
+    Serve {
+        address: SocketAddr,
+        players: usize,
+    },
+    Connect {
+        address: SocketAddr,
+    },
+}
+
+// Everything the game loop needs to know, built from the command line arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub difficulty: Difficulty,
+    pub range: GameRange,
+    pub attempts: u32,
+    pub seed: Option<u64>,
+    pub mode: Mode,
+    pub player: String,
+    pub leaderboard: PathBuf,
+}
+
+impl Config {
+    // Seeded games can be replayed and custom games can be made trivially small, so only plain presets are ranked.
+    pub fn is_ranked(&self) -> bool {
+        self.seed.is_none()
+            && self.range == self.difficulty.range()
+            && self.attempts == self.difficulty.attempts()
+    }
+
+    pub fn from_difficulty(difficulty: Difficulty) -> Config {
+        Config {
+            difficulty,
+            range: difficulty.range(),
+            attempts: difficulty.attempts(),
+            seed: None,
+            mode: Mode::Play,
+            player: String::from("anonymous"),
+            leaderboard: PathBuf::from(leaderboard::DEFAULT_PATH),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::from_difficulty(Difficulty::default())
+    }
+}
+
+// Parses the arguments that follow the program name, e.g. `std::env::args().skip(1)`.
+pub fn parse_args<I>(args: I) -> Result<Config, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut difficulty = Difficulty::default();
+    let mut min = None;
+    let mut max = None;
+    let mut attempts = None;
+    let mut seed = None;
+    let mut mode = Mode::Play;
+    let mut player = None;
+    let mut leaderboard = None;
+    let mut players = 2;
+    let mut strategy = None;
+
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+
+        match flag.as_str() {
+            "--difficulty" => difficulty = value()?.parse()?,
+            "--min" => min = Some(parse_number(&value()?)?),
+            "--max" => max = Some(parse_number(&value()?)?),
+            "--attempts" => {
+                let count = parse_number(&value()?)?;
+                if count < 1 {
+                    return Err(String::from("--attempts must be at least 1"));
+                }
+                attempts = Some(count as u32);
+            }
+            "--bench" => {
+                let games = parse_number(&value()?)?;
+                if games < 1 {
+                    return Err(String::from("--bench needs at least 1 game"));
+                }
+                mode = Mode::Bench { games: games as u32, strategy };
+            }
+            "--strategy" => strategy = Some(value()?.parse()?),
+            "--stats" => mode = Mode::Stats,
+            "--reverse" => mode = Mode::Reverse,
+            "--serve" => {
+                let address = parse_address(&value()?)?;
+                mode = Mode::Serve { address, players };
+            }
+            "--connect" => {
+                let address = parse_address(&value()?)?;
+                mode = Mode::Connect { address };
+            }
+            "--players" => {
+                let count = parse_number(&value()?)?;
+                if count < 1 {
+                    return Err(String::from("--players must be at least 1"));
+                }
+                players = count as usize;
+            }
+            "--player" => {
+                let name = value()?;
+                leaderboard::validate_player(&name)?;
+                player = Some(name);
+            }
+            "--leaderboard" => leaderboard = Some(PathBuf::from(value()?)),
+            "--seed" => {
+                let text = value()?;
+                seed = Some(
+                    text.parse()
+                        .map_err(|_| format!("'{}' is not a valid seed", text))?,
+                );
+            }
+            _ => return Err(format!("unknown argument '{}'", flag)),
+        }
+    }
+
+    // --players may come before or after --serve, and --strategy before or after --bench.
+    if let Mode::Serve { address, .. } = mode {
+        mode = Mode::Serve { address, players };
+    }
+    if let Mode::Bench { games, .. } = mode {
+        mode = Mode::Bench { games, strategy };
+    }
+
+    let preset = difficulty.range();
+    let low = min.unwrap_or(preset.low());
+    let high = max.unwrap_or(preset.high());
+    let range = GameRange::new(low, high)
+        .ok_or_else(|| format!("--min ({}) must not be greater than --max ({})", low, high))?;
+
+    let defaults = Config::from_difficulty(difficulty);
+    Ok(Config {
+        range,
+        attempts: attempts.unwrap_or(defaults.attempts),
+        seed,
+        mode,
+        player: player.unwrap_or(defaults.player),
+        leaderboard: leaderboard.unwrap_or(defaults.leaderboard),
+        ..defaults
+    })
+}
+
+fn parse_address(value: &str) -> Result<SocketAddr, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{}' is not an address like 127.0.0.1:7878", value))
+}
+
+fn parse_number(value: &str) -> Result<i32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{}' is not a whole number", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Config, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn defaults_to_medium() {
+        assert_eq!(parse(&[]), Ok(Config::default()));
+    }
+
+    #[test]
+    fn custom_range_overrides_preset() {
+        let config = parse(&["--difficulty", "easy", "--max", "20", "--attempts", "3"]).unwrap();
+        assert_eq!(config.range, GameRange::new(1, 20).unwrap());
+        assert_eq!(config.attempts, 3);
+        assert_eq!(config.difficulty, Difficulty::Easy);
+    }
+
+    #[test]
+    fn selects_bench_mode() {
+        let config = parse(&["--bench", "1000", "--seed", "5"]).unwrap();
+        assert_eq!(config.mode, Mode::Bench { games: 1000, strategy: None });
+        assert_eq!(config.seed, Some(5));
+        assert!(!config.is_ranked());
+
+        let config = parse(&["--strategy", "Linear", "--bench", "10"]).unwrap();
+        assert_eq!(config.mode, Mode::Bench { games: 10, strategy: Some(Strategy::Linear) });
+        assert!(parse(&["--bench", "10", "--strategy", "ternary"]).is_err());
+    }
+
+    #[test]
+    fn reads_player_and_leaderboard() {
+        let config = parse(&["--stats", "--player", "ana", "--leaderboard", "/tmp/board.tsv"]).unwrap();
+        assert_eq!(config.mode, Mode::Stats);
+        assert_eq!(config.player, "ana");
+        assert_eq!(config.leaderboard, PathBuf::from("/tmp/board.tsv"));
+        assert!(config.is_ranked());
+        assert!(parse(&["--player", ""]).is_err());
+    }
+
+    #[test]
+    fn reads_network_modes() {
+        let address: SocketAddr = "127.0.0.1:7878".parse().unwrap();
+        let config = parse(&["--players", "3", "--serve", "127.0.0.1:7878"]).unwrap();
+        assert_eq!(
+            config.mode,
+            Mode::Serve {
+                address,
+                players: 3
+            }
+        );
+
+        let config = parse(&["--connect", "127.0.0.1:7878"]).unwrap();
+        assert_eq!(config.mode, Mode::Connect { address });
+        assert!(parse(&["--connect", "localhost"]).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse(&["--min", "50", "--max", "10"]).is_err());
+        assert!(parse(&["--attempts", "0"]).is_err());
+        assert!(parse(&["--max"]).is_err());
+        assert!(parse(&["--colour"]).is_err());
+        assert!(parse(&["--seed", "-1"]).is_err());
+        assert!(parse(&["--bench", "0"]).is_err());
+    }
+}
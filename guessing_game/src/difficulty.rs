@@ -0,0 +1,123 @@
+use std::fmt;
+use std::str::FromStr;
+
+// The inclusive range the secret number and every guess have to fall in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameRange {
+    low: i32,
+    high: i32,
+}
+
+impl GameRange {
+    // Returns None when the bounds are the wrong way round.
+    pub fn new(low: i32, high: i32) -> Option<GameRange> {
+        if low > high {
+            return None;
+        }
+        Some(GameRange { low, high })
+    }
+
+    pub fn low(&self) -> i32 {
+        self.low
+    }
+
+    pub fn high(&self) -> i32 {
+        self.high
+    }
+
+    pub fn contains(&self, value: i32) -> bool {
+        (self.low..=self.high).contains(&value)
+    }
+
+    // How many numbers the range holds. i64 because a range over all of i32 does not fit in a u32.
+    pub fn size(&self) -> i64 {
+        i64::from(self.high) - i64::from(self.low) + 1
+    }
+}
+
+impl Default for GameRange {
+    fn default() -> GameRange {
+        GameRange { low: 1, high: 100 }
+    }
+}
+
+impl fmt::Display for GameRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} and {}", self.low, self.high)
+    }
+}
+
+// Presets pairing a range with the number of guesses the player gets.
+// Medium and Hard give exactly as many attempts as a perfect binary search needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn range(&self) -> GameRange {
+        match self {
+            Difficulty::Easy => GameRange { low: 1, high: 50 },
+            Difficulty::Medium => GameRange { low: 1, high: 100 },
+            Difficulty::Hard => GameRange { low: 1, high: 1000 },
+        }
+    }
+
+    pub fn attempts(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 10,
+            Difficulty::Medium => 7,
+            Difficulty::Hard => 10,
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Difficulty, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!("unknown difficulty '{}', expected easy, medium or hard", s)),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_reversed_bounds() {
+        assert_eq!(GameRange::new(10, 1), None);
+        assert_eq!(GameRange::new(5, 5).unwrap().size(), 1);
+    }
+
+    #[test]
+    fn size_does_not_overflow() {
+        let everything = GameRange::new(i32::MIN, i32::MAX).unwrap();
+        assert_eq!(everything.size(), 1 << 32);
+    }
+
+    #[test]
+    fn parses_difficulty_names() {
+        assert_eq!("HARD".parse(), Ok(Difficulty::Hard));
+        assert!("impossible".parse::<Difficulty>().is_err());
+    }
+}
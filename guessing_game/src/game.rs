@@ -0,0 +1,113 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+
+use crate::cli::Config;
+use crate::guess::Guess;
+use crate::hint::Hint;
+
+// How a single game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won { guesses: u32 },
+    Lost,
+    // The input ran out before the game was decided.
+    Abandoned,
+}
+
+// One round of the game. It never touches stdin or stdout directly, so a session can be replayed from any BufRead
+// and the transcript checked from any Write.
+pub struct Game {
+    config: Config,
+    secret: i32,
+}
+
+impl Game {
+    // Panics if the secret is outside the configured range.
+    pub fn new(config: Config, secret: i32) -> Game {
+        assert!(
+            config.range.contains(secret),
+            "secret {} is not between {}",
+            secret,
+            config.range
+        );
+        Game { config, secret }
+    }
+
+    // Picks the secret with `config.seed` when one is given, so the same seed always gives the same number.
+    pub fn from_config(config: Config) -> Game {
+        let secret = match config.seed {
+            Some(seed) => Game::secret_from(&config, &mut StdRng::seed_from_u64(seed)),
+            None => Game::secret_from(&config, &mut rand::thread_rng()),
+        };
+        Game::new(config, secret)
+    }
+
+    fn secret_from(config: &Config, rng: &mut impl Rng) -> i32 {
+        rng.gen_range(config.range.low()..=config.range.high()) // low..=high is similiar to start..=end, which is a range that includes the start and end numbers.
+    }
+
+    pub fn secret(&self) -> i32 {
+        self.secret
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn play<R: BufRead, W: Write>(&self, mut input: R, mut output: W) -> io::Result<Outcome> {
+        let range = self.config.range;
+        writeln!(output, "Guess the number!")?;
+        writeln!(
+            output,
+            "Difficulty: {}. The number is between {}, and you have {} attempts.",
+            self.config.difficulty, range, self.config.attempts
+        )?;
+
+        let mut previous = None;
+        let mut guesses = 0;
+
+        while guesses < self.config.attempts {
+            writeln!(
+                output,
+                "Please input your guess. ({} attempts left)",
+                self.config.attempts - guesses
+            )?;
+
+            let mut line = String::new(); // Mutable(Changeable) variable to store the user input
+            if input.read_line(&mut line)? == 0 {
+                writeln!(output, "No more input, the number was {}.", self.secret)?;
+                return Ok(Outcome::Abandoned);
+            } // read_line returns 0 bytes once the input is closed, so stop instead of asking forever.
+
+            let guess = match Guess::try_new(&line, range) {
+                Ok(guess) => guess,
+                Err(error) => {
+                    writeln!(output, "{}", error)?;
+                    continue;
+                } // Each GuessError variant explains what was wrong with the input. Invalid input does not use up an attempt.
+            };
+
+            writeln!(output, "You guessed: {}", guess.value())?;
+            guesses += 1;
+
+            match guess.value().cmp(&self.secret) {
+                Ordering::Less => writeln!(output, "Too small!")?,
+                Ordering::Greater => writeln!(output, "Too big!")?,
+                Ordering::Equal => {
+                    writeln!(output, "You win!")?;
+                    return Ok(Outcome::Won { guesses });
+                }
+            }
+
+            if let Some(hint) = Hint::between(self.secret, previous, guess.value()) {
+                writeln!(output, "{}", hint)?;
+            }
+            previous = Some(guess.value());
+        }
+
+        writeln!(output, "You lose! The number was {}.", self.secret)?;
+        Ok(Outcome::Lost)
+    }
+}
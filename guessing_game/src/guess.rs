@@ -0,0 +1,114 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::difficulty::GameRange;
+
+pub struct Guess {
+    value: i32,
+}
+
+// Why a line of player input could not be turned into a Guess.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    Empty,
+    NotANumber(String),
+    // i64 so that numbers too big for an i32 are still reported as out of range rather than as garbage.
+    OutOfRange { value: i64, range: GameRange },
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GuessError::Empty => write!(f, "Please type a number before pressing enter."),
+            GuessError::NotANumber(input) => write!(f, "'{}' is not a whole number.", input),
+            GuessError::OutOfRange { value, range } => write!(
+                f,
+                "Guess value must be between {} and {}, got {}.",
+                range.low(),
+                range.high(),
+                value
+            ),
+        }
+    }
+}
+
+impl Error for GuessError {}
+
+impl Guess {
+    // Validates against the classic 1..=100 range.
+    pub fn new(value: i32) -> Guess {
+        Guess::with_range(value, GameRange::default())
+    }
+
+    // Panics if the value is outside the range, see try_new for the non-panicking version.
+    pub fn with_range(value: i32, range: GameRange) -> Guess {
+        match Guess::check(i64::from(value), range) {
+            Ok(guess) => guess,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    // Parses a line of player input, surrounding whitespace is ignored.
+    pub fn try_new(input: &str, range: GameRange) -> Result<Guess, GuessError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(GuessError::Empty);
+        }
+
+        let value: i64 = input
+            .parse()
+            .map_err(|_| GuessError::NotANumber(String::from(input)))?;
+        Guess::check(value, range)
+    }
+
+    fn check(value: i64, range: GameRange) -> Result<Guess, GuessError> {
+        match i32::try_from(value) {
+            Ok(value) if range.contains(value) => Ok(Guess { value }),
+            _ => Err(GuessError::OutOfRange { value, range }),
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Difficulty;
+
+    #[test]
+    #[should_panic(expected = "must be between 1 and 100")]
+    fn greater_than_100() {
+        Guess::new(200);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be between 1 and 1000, got 0")]
+    fn outside_custom_range() {
+        Guess::with_range(0, Difficulty::Hard.range());
+    }
+
+    #[test]
+    fn inside_custom_range() {
+        let range = GameRange::new(-10, 10).unwrap();
+        assert_eq!(Guess::with_range(-10, range).value(), -10);
+    }
+
+    #[test]
+    fn try_new_reports_each_failure() {
+        let range = GameRange::default();
+
+        assert_eq!(Guess::try_new(" 42\n", range).unwrap().value(), 42);
+        assert_eq!(Guess::try_new("  \n", range).err(), Some(GuessError::Empty));
+        assert_eq!(
+            Guess::try_new("forty", range).err(),
+            Some(GuessError::NotANumber(String::from("forty")))
+        );
+        assert_eq!(
+            Guess::try_new("10000000000", range).err(),
+            Some(GuessError::OutOfRange { value: 10_000_000_000, range })
+        );
+    }
+}
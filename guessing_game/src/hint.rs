@@ -0,0 +1,50 @@
+use std::fmt;
+
+// Tells the player whether their latest guess got closer to the secret than the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    Warmer,
+    Colder,
+    Same,
+}
+
+impl Hint {
+    // There is nothing to compare the first guess with, so it gets no hint.
+    pub fn between(secret: i32, previous: Option<i32>, current: i32) -> Option<Hint> {
+        let previous = previous?;
+        let before = (i64::from(previous) - i64::from(secret)).abs();
+        let now = (i64::from(current) - i64::from(secret)).abs();
+
+        Some(if now < before {
+            Hint::Warmer
+        } else if now > before {
+            Hint::Colder
+        } else {
+            Hint::Same
+        })
+    }
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Hint::Warmer => "Getting warmer!",
+            Hint::Colder => "Getting colder!",
+            Hint::Same => "Just as far away as last time.",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_distance_to_secret() {
+        assert_eq!(Hint::between(50, None, 10), None);
+        assert_eq!(Hint::between(50, Some(10), 40), Some(Hint::Warmer));
+        assert_eq!(Hint::between(50, Some(40), 90), Some(Hint::Colder));
+        assert_eq!(Hint::between(50, Some(40), 60), Some(Hint::Same));
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::difficulty::Difficulty;
+
+pub const DEFAULT_PATH: &str = "leaderboard.tsv";
+
+// One won game. Stored as a tab separated line: player, difficulty, guesses, elapsed milliseconds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub player: String,
+    pub difficulty: Difficulty,
+    pub guesses: u32,
+    pub elapsed: Duration,
+}
+
+impl Record {
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}",
+            self.player,
+            self.difficulty,
+            self.guesses,
+            self.elapsed.as_millis()
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Record> {
+        let mut fields = line.split('\t');
+        let record = Record {
+            player: String::from(fields.next()?),
+            difficulty: fields.next()?.parse().ok()?,
+            guesses: fields.next()?.parse().ok()?,
+            elapsed: Duration::from_millis(fields.next()?.parse().ok()?),
+        };
+        if fields.next().is_some() || record.player.is_empty() {
+            return None;
+        }
+        Some(record)
+    }
+}
+
+// Player names end up in a tab separated file, so they must be non-empty and fit on one field.
+pub fn validate_player(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err(String::from("player name must not be empty"));
+    }
+    if name.contains(['\t', '\n', '\r']) {
+        return Err(String::from("player name must not contain tabs or line breaks"));
+    }
+    Ok(())
+}
+
+// A player's results on one difficulty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerStats {
+    pub player: String,
+    pub difficulty: Difficulty,
+    pub games: u32,
+    pub best_guesses: u32,
+    pub best_time: Duration,
+    pub average_guesses: f64,
+    pub average_time: Duration,
+}
+
+#[derive(Debug, Default)]
+pub struct Leaderboard {
+    records: Vec<Record>,
+}
+
+impl Leaderboard {
+    // A missing file is an empty leaderboard, a line that cannot be read is an InvalidData error naming the line.
+    pub fn load(path: &Path) -> io::Result<Leaderboard> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Leaderboard::default()),
+            Err(error) => return Err(error),
+        };
+
+        let mut records = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = Record::from_line(line).ok_or_else(|| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}:{}: malformed leaderboard entry", path.display(), number + 1),
+                )
+            })?;
+            records.push(record);
+        }
+
+        Ok(Leaderboard { records })
+    }
+
+    // Adds one record to the end of the file, creating it if needed, so earlier results are never rewritten.
+    pub fn append(path: &Path, record: &Record) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", record.to_line())
+    }
+
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    pub fn add(&mut self, record: Record) {
+        self.records.push(record);
+    }
+
+    // Stats per player and difficulty, ordered by difficulty and then by score:
+    // fewest guesses in the best game, then the fastest best time, then the name.
+    pub fn stats(&self) -> Vec<PlayerStats> {
+        let mut grouped: BTreeMap<(String, Difficulty), Vec<&Record>> = BTreeMap::new();
+        for record in &self.records {
+            grouped
+                .entry((record.player.clone(), record.difficulty))
+                .or_default()
+                .push(record);
+        }
+
+        let mut stats: Vec<PlayerStats> = grouped
+            .into_iter()
+            .map(|((player, difficulty), records)| {
+                let games = records.len() as u32;
+                let total_guesses: u64 = records.iter().map(|r| u64::from(r.guesses)).sum();
+                let total_time: Duration = records.iter().map(|r| r.elapsed).sum();
+                let best = records
+                    .iter()
+                    .min_by_key(|r| (r.guesses, r.elapsed))
+                    .expect("every group has at least one record");
+
+                PlayerStats {
+                    player,
+                    difficulty,
+                    games,
+                    best_guesses: best.guesses,
+                    best_time: best.elapsed,
+                    average_guesses: total_guesses as f64 / f64::from(games),
+                    average_time: total_time / games,
+                }
+            })
+            .collect();
+
+        stats.sort_by(|a, b| {
+            (a.difficulty, a.best_guesses, a.best_time, &a.player)
+                .cmp(&(b.difficulty, b.best_guesses, b.best_time, &b.player))
+        });
+        stats
+    }
+
+    pub fn table(&self) -> String {
+        let stats = self.stats();
+        if stats.is_empty() {
+            return String::from("No games recorded yet.\n");
+        }
+
+        let mut table = format!(
+            "{:<10} {:<16} {:>5} {:>5} {:>9} {:>9} {:>9}\n",
+            "difficulty", "player", "games", "best", "avg", "best time", "avg time"
+        );
+        for row in stats {
+            writeln!(
+                table,
+                "{:<10} {:<16} {:>5} {:>5} {:>9.2} {:>8.1}s {:>8.1}s",
+                row.difficulty.to_string(),
+                row.player,
+                row.games,
+                row.best_guesses,
+                row.average_guesses,
+                row.best_time.as_secs_f64(),
+                row.average_time.as_secs_f64()
+            )
+            .unwrap();
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(player: &str, difficulty: Difficulty, guesses: u32, secs: u64) -> Record {
+        Record {
+            player: String::from(player),
+            difficulty,
+            guesses,
+            elapsed: Duration::from_secs(secs),
+        }
+    }
+
+    #[test]
+    fn tracks_personal_best_and_average() {
+        let mut board = Leaderboard::default();
+        board.add(record("ana", Difficulty::Medium, 6, 30));
+        board.add(record("ana", Difficulty::Medium, 4, 50));
+        board.add(record("ana", Difficulty::Medium, 5, 10));
+
+        let stats = board.stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].games, 3);
+        assert_eq!(stats[0].best_guesses, 4);
+        assert_eq!(stats[0].best_time, Duration::from_secs(50));
+        assert_eq!(stats[0].average_guesses, 5.0);
+        assert_eq!(stats[0].average_time, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn sorts_by_difficulty_then_score() {
+        let mut board = Leaderboard::default();
+        board.add(record("slow", Difficulty::Easy, 3, 90));
+        board.add(record("hard", Difficulty::Hard, 1, 1));
+        board.add(record("fast", Difficulty::Easy, 3, 20));
+        board.add(record("best", Difficulty::Easy, 2, 99));
+
+        let order: Vec<_> = board.stats().into_iter().map(|s| s.player).collect();
+        assert_eq!(order, ["best", "fast", "slow", "hard"]);
+    }
+
+    #[test]
+    fn round_trips_through_a_file() {
+        let path = std::env::temp_dir().join(format!("leaderboard-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        assert!(Leaderboard::load(&path).unwrap().records().is_empty());
+        let first = record("ana", Difficulty::Hard, 9, 12);
+        Leaderboard::append(&path, &first).unwrap();
+        Leaderboard::append(&path, &record("bo", Difficulty::Easy, 3, 4)).unwrap();
+
+        let board = Leaderboard::load(&path).unwrap();
+        assert_eq!(board.records().len(), 2);
+        assert_eq!(board.records()[0], first);
+
+        fs::write(&path, "ana\thard\tnine\t12\n").unwrap();
+        let error = Leaderboard::load(&path).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_names_that_break_the_file() {
+        assert!(validate_player("ana").is_ok());
+        assert!(validate_player(" ").is_err());
+        assert!(validate_player("a\tb").is_err());
+    }
+}
@@ -0,0 +1,18 @@
+// The game logic lives in a library crate so the binary in main.rs only has to deal with input and output.
+pub mod bench;
+pub mod cli;
+pub mod difficulty;
+pub mod game;
+pub mod guess;
+pub mod hint;
+pub mod leaderboard;
+pub mod reverse;
+pub mod server;
+pub mod solver;
+
+pub use crate::difficulty::{Difficulty, GameRange};
+pub use crate::game::{Game, Outcome};
+pub use crate::guess::{Guess, GuessError};
+pub use crate::hint::Hint;
+pub use crate::leaderboard::{Leaderboard, Record};
+pub use crate::solver::{Solver, Strategy};
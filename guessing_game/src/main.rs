@@ -1,73 +1,146 @@
-use rand::Rng; use core::panic;
-// rand library used to generate random numbers
+// rand library used to generate random numbers, see Game::from_config in src/game.rs
 // use std::cmp::Ordering;
 // use std::io; // io library used to take input from user and for output operations // cmp library used to compare two values
              // cargo doc --open to open the documentation of the libraries used in the project
-use std::{cmp::Ordering, io}; // This is an idiomatic way to bring multiple items with the same prefix into scope. This is the same as the previous two lines, but it's more concise.
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::Instant;
+use std::{env, io, process}; // This is an idiomatic way to bring multiple items with the same prefix into scope. This is the same as the previous two lines, but it's more concise.
 
-pub struct Guess {
-    value: i32,
-}
+use guessing_game::cli::{self, Config, Mode};
+use guessing_game::server::{self, MatchOutcome};
+use guessing_game::{bench, reverse, Difficulty, Game, Guess, Leaderboard, Outcome, Record, Strategy};
 
-impl Guess {
-    pub fn new(value: i32) -> Guess {
-        if value < 1 || value > 100 {
-            panic!("Guess value must be between 1 and 100, got {}.", value);
+fn main() {
+    let config = match cli::parse_args(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, cli::USAGE);
+            process::exit(2);
         }
+    };
 
-        Guess { value }
+    match config.mode {
+        Mode::Play => play(config),
+        Mode::Bench { games, strategy } => benchmark(&config, games, strategy),
+        Mode::Stats => stats(&config),
+        Mode::Reverse => {
+            reverse::play(config.range, io::stdin().lock(), io::stdout())
+                .expect("Failed to read line");
+        }
+        Mode::Serve { address, players } => serve(&config, address, players),
+        Mode::Connect { address } => connect(address),
     }
+}
+
+fn play(config: Config) {
+    let game = Game::from_config(config);
+    let started = Instant::now();
+    // The game reads from any BufRead and writes to any Write. Here that is the terminal, in the integration tests it is a scripted session.
+    // lock() gives us a handle that implements BufRead, so each read_line does not have to lock stdin again.
+    let outcome = game
+        .play(io::stdin().lock(), io::stdout())
+        .expect("Failed to read line"); // expect is a method of Result type, which is used to handle errors. If the Result is an Err value, expect will cause the program to crash and display the message that you passed as an argument to expect.
 
-    pub fn value(&self) -> i32 {
-        self.value
+    if let Outcome::Won { guesses } = outcome {
+        record_win(game.config(), guesses, started);
     }
 }
 
-fn main() {
-    println!("Guess the number!");
+fn record_win(config: &Config, guesses: u32, started: Instant) {
+    if !config.is_ranked() {
+        println!("Seeded and custom games are not added to the leaderboard.");
+        return;
+    }
 
-    let secret_number = rand::thread_rng().gen_range(1..=100); // Generate a random number between 1 and 100
-                                                               // 1..=100 is similiar to start..=end, which is a range that includes the start and end numbers.
+    let record = Record {
+        player: config.player.clone(),
+        difficulty: config.difficulty,
+        guesses,
+        elapsed: started.elapsed(),
+    };
+    if let Err(error) = Leaderboard::append(&config.leaderboard, &record) {
+        eprintln!("Could not save your result: {}", error);
+        return;
+    }
 
-    loop {
-        println!("Please input your guess.");
+    match Leaderboard::load(&config.leaderboard) {
+        Ok(board) => {
+            let best = board.stats().into_iter().find(|stats| {
+                stats.player == record.player && stats.difficulty == record.difficulty
+            });
+            if let Some(best) = best {
+                println!(
+                    "{}'s best on {}: {} guesses over {} games, {:.2} on average.",
+                    best.player, best.difficulty, best.best_guesses, best.games, best.average_guesses
+                );
+            }
+        }
+        Err(error) => eprintln!("Could not read the leaderboard: {}", error),
+    }
+}
 
-        let mut guess = String::new(); // Mutable(Changeable) variable to store the user input
-                                       // :: is used to callan associated function of a type, in this case, new is an associated function of String type.
+fn stats(config: &Config) {
+    match Leaderboard::load(&config.leaderboard) {
+        Ok(board) => print!("{}", board.table()),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    }
+}
 
-        io::stdin()
-            .read_line(&mut guess)
-            .expect("Failed to read line"); // Read the input from user and store it in the mutable variable.
-                                            // & indicates that this argument is a reference, which gives you a way to let multiple parts of your code access one piece of data without needing to copy that data into memory multiple times.
-                                            // expect is a method of Result type, which is used to handle errors. If the Result is an Err value, expect will cause the program to crash and display the message that you passed as an argument to expect.
+// Compares the solver strategies, or just the one given with --strategy, on every preset range, plus the range from the command line if it is a custom one.
+fn benchmark(config: &Config, games: u32, strategy: Option<Strategy>) {
+    let mut ranges: Vec<_> = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard]
+        .iter()
+        .map(Difficulty::range)
+        .collect();
+    if !ranges.contains(&config.range) {
+        ranges.push(config.range);
+    }
 
-        let guess = match guess.trim().parse() {
-            Ok(num) => Guess::new(num), // Ok is an enum variant that indicates that the parse operation was successful. num is the number that was parsed from the string.
-            Err(_) => continue, // _ is a catchall value. In this case, we’re saying that we want to match all Err values, no matter what information they have inside them. The continue keyword tells the program to go to the next iteration of the loop.
-        }; // Convert the user input to a number
-           // Shadowing lets us reuse the variable. trim() method eliminates the whitespace from the string. parse() method parses the string into a number. u32 is an unsigned 32-bit integer.
+    let strategies = match strategy {
+        Some(strategy) => vec![strategy],
+        None => Strategy::ALL.to_vec(),
+    };
+    let results = bench::run_all(&ranges, &strategies, games, config.seed.unwrap_or(0));
+    print!("{}", bench::report(&results));
+}
 
-        println!("You guessed: {}", guess.value()); // Print the user input
+fn serve(config: &Config, address: SocketAddr, players: usize) {
+    let listener = TcpListener::bind(address).unwrap_or_else(|error| {
+        eprintln!("error: could not listen on {}: {}", address, error);
+        process::exit(1);
+    });
 
-        match guess.value.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal => {
-                println!("You win!");
-                break;
-            }
+    let secret = loop {
+        println!("Pick the secret number between {}:", config.range);
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).expect("Failed to read line") == 0 {
+            return;
         }
+        match Guess::try_new(&line, config.range) {
+            Ok(guess) => break guess.value(),
+            Err(error) => println!("{}", error),
+        }
+    };
+
+    println!("Waiting for {} players on {}...", players, address);
+    match server::host(&listener, config.range, secret, players) {
+        Ok(MatchOutcome::Winner { name, guesses }) => {
+            println!("{} found the number in {} guesses.", name, guesses)
+        }
+        Ok(MatchOutcome::Abandoned) => println!("Everyone left before the number was found."),
+        Err(error) => eprintln!("error: {}", error),
     }
-    // match expression is made up of arms. An arm consists of a pattern and the code that should be run if the value given to the beginning of the match expression fits that arm’s pattern. Rust takes the value given to match and looks through each arm’s pattern in turn. The match construct and the arms are separated by curly braces.
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    #[should_panic(expected = "must be between 1 and 100")]
-    fn greater_than_100() {
-        Guess::new(200);
+fn connect(address: SocketAddr) {
+    let stream = TcpStream::connect(address).unwrap_or_else(|error| {
+        eprintln!("error: could not connect to {}: {}", address, error);
+        process::exit(1);
+    });
+    if let Err(error) = server::join(stream, io::BufReader::new(io::stdin()), io::stdout()) {
+        eprintln!("error: {}", error);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,241 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use crate::difficulty::GameRange;
+use crate::solver::{Solver, Strategy};
+
+// What the player says about the program's guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Higher,
+    Lower,
+    Correct,
+}
+
+impl FromStr for Answer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Answer, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "h" | "higher" => Ok(Answer::Higher),
+            "l" | "lower" => Ok(Answer::Lower),
+            "c" | "correct" => Ok(Answer::Correct),
+            other => Err(format!("'{}' is not an answer, type h, l or c.", other)),
+        }
+    }
+}
+
+// Where a bound on the player's number came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    // The edge of the range the game was started with.
+    Range(i32),
+    // An earlier answer about this guess.
+    Answer(i32),
+}
+
+// An answer that leaves no number the player could be thinking of.
+// Only play builds these, and only for a higher or lower answer, so the fields are private.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contradiction {
+    guess: i32,
+    answer: Answer,
+    conflicts_with: Bound,
+}
+
+impl Contradiction {
+    pub fn guess(&self) -> i32 {
+        self.guess
+    }
+
+    pub fn answer(&self) -> Answer {
+        self.answer
+    }
+
+    pub fn conflicts_with(&self) -> Bound {
+        self.conflicts_with
+    }
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (direction, other) = match self.answer {
+            Answer::Higher => ("higher", "lower"),
+            Answer::Lower => ("lower", "higher"),
+            Answer::Correct => {
+                unreachable!("the program only guesses numbers that are still possible")
+            }
+        };
+        write!(
+            f,
+            "You said your number is {} than {}, ",
+            direction, self.guess
+        )?;
+        match self.conflicts_with {
+            Bound::Range(edge) => write!(f, "but the range ends at {}.", edge),
+            Bound::Answer(earlier) => {
+                write!(f, "but earlier you said it is {} than {}.", other, earlier)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReverseOutcome {
+    Guessed { number: i32, guesses: u32 },
+    Contradiction(Contradiction),
+    Abandoned,
+}
+
+// The player thinks of a number in `range` and answers the program's guesses, which come from a binary search.
+pub fn play<R: BufRead, W: Write>(
+    range: GameRange,
+    mut input: R,
+    mut output: W,
+) -> io::Result<ReverseOutcome> {
+    writeln!(
+        output,
+        "Think of a number between {} and I will guess it.",
+        range
+    )?;
+    writeln!(
+        output,
+        "Answer h if your number is higher, l if it is lower and c if I got it right."
+    )?;
+
+    let mut solver = Solver::new(Strategy::Binary, range, 0);
+    let mut lower_bound = Bound::Range(range.low());
+    let mut upper_bound = Bound::Range(range.high());
+    let mut guesses = 0;
+
+    // The solver only offers numbers that are still possible, and every answer rules at least that number out,
+    // so this loop ends with a correct answer or a contradiction.
+    while let Some(guess) = solver.next_guess() {
+        guesses += 1;
+
+        let answer = loop {
+            writeln!(output, "Is it {}?", guess)?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                writeln!(output, "No more input, giving up.")?;
+                return Ok(ReverseOutcome::Abandoned);
+            }
+            match line.parse::<Answer>() {
+                Ok(answer) => break answer,
+                Err(message) => writeln!(output, "{}", message)?,
+            }
+        };
+
+        let (low, high) = solver.remaining().expect("the solver just made a guess");
+        let contradiction = match answer {
+            Answer::Correct => {
+                writeln!(
+                    output,
+                    "I got it! Your number is {}, found in {} guesses.",
+                    guess, guesses
+                )?;
+                return Ok(ReverseOutcome::Guessed {
+                    number: guess,
+                    guesses,
+                });
+            }
+            Answer::Higher if guess >= high => Some(upper_bound),
+            Answer::Lower if guess <= low => Some(lower_bound),
+            Answer::Higher => {
+                lower_bound = Bound::Answer(guess);
+                None
+            }
+            Answer::Lower => {
+                upper_bound = Bound::Answer(guess);
+                None
+            }
+        };
+
+        if let Some(conflicts_with) = contradiction {
+            let contradiction = Contradiction {
+                guess,
+                answer,
+                conflicts_with,
+            };
+            writeln!(output, "{}", contradiction)?;
+            return Ok(ReverseOutcome::Contradiction(contradiction));
+        }
+
+        // Higher means the guess is below the player's number, just like Too small! in the normal game.
+        let ordering = match answer {
+            Answer::Higher => Ordering::Less,
+            _ => Ordering::Greater,
+        };
+        solver.feedback(guess, ordering);
+    }
+
+    unreachable!("a consistent set of answers always leaves a number to guess")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(range: GameRange, script: &str) -> (ReverseOutcome, String) {
+        let mut output = Vec::new();
+        let outcome = play(range, script.as_bytes(), &mut output).unwrap();
+        (outcome, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn finds_the_players_number() {
+        // 50 -> higher, 75 -> lower, 62 -> higher, 68 -> correct
+        let (outcome, output) = run(GameRange::default(), "h\nlower\nwhat\nH\nc\n");
+
+        assert_eq!(
+            outcome,
+            ReverseOutcome::Guessed {
+                number: 68,
+                guesses: 4
+            }
+        );
+        assert!(output.contains("'what' is not an answer"));
+    }
+
+    #[test]
+    fn higher_past_the_top_of_the_range() {
+        let range = GameRange::new(1, 3).unwrap();
+        // 2 -> higher, 3 -> higher
+        let (outcome, output) = run(range, "h\nh\n");
+
+        assert_eq!(
+            outcome,
+            ReverseOutcome::Contradiction(Contradiction {
+                guess: 3,
+                answer: Answer::Higher,
+                conflicts_with: Bound::Range(3),
+            })
+        );
+        assert!(
+            output.ends_with("You said your number is higher than 3, but the range ends at 3.\n")
+        );
+    }
+
+    #[test]
+    fn lower_than_an_earlier_higher() {
+        // 50 -> higher, 75 -> lower, 62 -> lower, 56 -> lower, 53 -> lower, 51 -> lower
+        let (outcome, output) = run(GameRange::default(), "h\nl\nl\nl\nl\nl\n");
+
+        assert_eq!(
+            outcome,
+            ReverseOutcome::Contradiction(Contradiction {
+                guess: 51,
+                answer: Answer::Lower,
+                conflicts_with: Bound::Answer(50),
+            })
+        );
+        assert!(output.ends_with("but earlier you said it is higher than 50.\n"));
+    }
+
+    #[test]
+    fn gives_up_without_input() {
+        assert_eq!(run(GameRange::default(), "").0, ReverseOutcome::Abandoned);
+    }
+}
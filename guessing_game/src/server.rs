@@ -0,0 +1,224 @@
+use std::cmp::Ordering;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use crate::difficulty::GameRange;
+use crate::guess::Guess;
+
+// How a networked game ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchOutcome {
+    Winner { name: String, guesses: u32 },
+    // Every player disconnected before anyone found the number.
+    Abandoned,
+}
+
+// How long host waits for a player to send their name or their guess before treating them as gone.
+pub const PLAYER_TIMEOUT: Duration = Duration::from_secs(120);
+
+struct Player {
+    name: String,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    guesses: u32,
+}
+
+impl Player {
+    // Write errors are ignored here, a player who went away is noticed the next time we read from them.
+    fn send(&mut self, message: &str) {
+        let _ = writeln!(self.writer, "{}", message);
+    }
+
+    // None when the player hung up or did not say anything in time.
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(line)),
+            Err(error) if is_timeout(&error) => {
+                self.send("You took too long, goodbye.");
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
+
+// Which of the two a timed out read returns depends on the platform.
+fn is_timeout(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+// Waits for `player_count` players to connect to `listener`, then lets them take turns guessing `secret`.
+// The protocol is plain lines of text: each client first sends its name, then one guess per turn.
+// A player silent for longer than PLAYER_TIMEOUT is dropped, as if they had disconnected.
+pub fn host(
+    listener: &TcpListener,
+    range: GameRange,
+    secret: i32,
+    player_count: usize,
+) -> io::Result<MatchOutcome> {
+    host_with_timeout(listener, range, secret, player_count, PLAYER_TIMEOUT)
+}
+
+// Like host, but players are dropped after `timeout` of silence instead. A zero `timeout` is an InvalidInput error.
+pub fn host_with_timeout(
+    listener: &TcpListener,
+    range: GameRange,
+    secret: i32,
+    player_count: usize,
+    timeout: Duration,
+) -> io::Result<MatchOutcome> {
+    assert!(
+        range.contains(secret),
+        "secret {} is not between {}",
+        secret,
+        range
+    );
+    // Checked here, set_read_timeout would only refuse it once the first client has connected.
+    if timeout.is_zero() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the player timeout must not be zero",
+        ));
+    }
+
+    let mut players = Vec::new();
+    while players.len() < player_count {
+        // Everyone still missing is let in before any names are read, so a quiet client does not keep
+        // the ones after it from connecting. Clients that never give a name leave a place for the next round.
+        let mut joining = Vec::new();
+        for _ in players.len()..player_count {
+            let (stream, _) = listener.accept()?;
+            stream.set_read_timeout(Some(timeout))?;
+            let mut player = Player {
+                name: String::new(),
+                reader: BufReader::new(stream.try_clone()?),
+                writer: stream,
+                guesses: 0,
+            };
+            player.send("Welcome to the guessing game! What is your name?");
+            joining.push(player);
+        }
+
+        for mut player in joining {
+            let name = match player.read_line() {
+                Ok(Some(name)) => name.trim().to_string(),
+                // A client that goes away or stays quiet before telling us its name never joined.
+                Ok(None) | Err(_) => continue,
+            };
+            player.name = unique_name(&players, &name);
+
+            broadcast(&mut players, &format!("{} joined the game.", player.name));
+            player.send(&format!(
+                "Hello {}! Waiting for {} more players.",
+                player.name,
+                player_count - players.len() - 1
+            ));
+            players.push(player);
+        }
+    }
+
+    broadcast(
+        &mut players,
+        &format!("Everyone is here. The number is between {}.", range),
+    );
+
+    let mut turn = 0;
+    while !players.is_empty() {
+        turn %= players.len();
+        let name = players[turn].name.clone();
+        for (index, player) in players.iter_mut().enumerate() {
+            if index == turn {
+                player.send("Your turn. Please input your guess.");
+            } else {
+                player.send(&format!("Waiting for {} to guess.", name));
+            }
+        }
+
+        let line = match players[turn].read_line() {
+            Ok(Some(line)) => line,
+            Ok(None) | Err(_) => {
+                players.remove(turn);
+                broadcast(&mut players, &format!("{} left the game.", name));
+                continue;
+            }
+        };
+
+        // The server does the validating, so a client cannot skip the range check.
+        let guess = match Guess::try_new(&line, range) {
+            Ok(guess) => guess,
+            Err(error) => {
+                players[turn].send(&error.to_string());
+                continue;
+            }
+        };
+        players[turn].guesses += 1;
+
+        let result = match guess.value().cmp(&secret) {
+            Ordering::Less => "Too small!",
+            Ordering::Greater => "Too big!",
+            Ordering::Equal => {
+                let guesses = players[turn].guesses;
+                broadcast(
+                    &mut players,
+                    &format!("{} guessed {}: {} wins!", name, guess.value(), name),
+                );
+                return Ok(MatchOutcome::Winner { name, guesses });
+            }
+        };
+        broadcast(
+            &mut players,
+            &format!("{} guessed {}: {}", name, guess.value(), result),
+        );
+        turn += 1;
+    }
+
+    Ok(MatchOutcome::Abandoned)
+}
+
+fn broadcast(players: &mut [Player], message: &str) {
+    for player in players {
+        player.send(message);
+    }
+}
+
+// Falls back to "player" for empty names and adds a number when the name is already taken.
+fn unique_name(players: &[Player], wanted: &str) -> String {
+    let base = if wanted.is_empty() { "player" } else { wanted };
+    let taken = |name: &str| players.iter().any(|player| player.name == name);
+
+    if !taken(base) {
+        return String::from(base);
+    }
+    (2..)
+        .map(|n| format!("{}{}", base, n))
+        .find(|name| !taken(name))
+        .expect("there is always a free name")
+}
+
+// Connects to a game and relays lines between the terminal and the server until the server hangs up.
+pub fn join<R, W>(stream: TcpStream, mut input: R, mut output: W) -> io::Result<()>
+where
+    R: BufRead + Send + 'static,
+    W: Write,
+{
+    let mut to_server = stream.try_clone()?;
+    // Typing happens on its own thread, so messages from the server show up while we wait for our turn.
+    std::thread::spawn(move || {
+        let mut line = String::new();
+        while matches!(input.read_line(&mut line), Ok(n) if n > 0) {
+            if to_server.write_all(line.as_bytes()).is_err() {
+                break;
+            }
+            line.clear();
+        }
+    });
+
+    io::copy(&mut BufReader::new(stream), &mut output)?;
+    Ok(())
+}
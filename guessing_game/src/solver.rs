@@ -0,0 +1,167 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::difficulty::GameRange;
+
+// Ways the computer can search for the secret using only the Too small!/Too big! feedback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    // Always guesses the middle of what is left, never needs more than log2(size) + 1 guesses.
+    Binary,
+    // Guesses a third of the way into what is left, to show what an off-centre split costs.
+    Skewed,
+    // Guesses anywhere in what is left.
+    Random,
+    // Counts up from the bottom of the range.
+    Linear,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::Binary,
+        Strategy::Skewed,
+        Strategy::Random,
+        Strategy::Linear,
+    ];
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Strategy, String> {
+        Strategy::ALL
+            .into_iter()
+            .find(|strategy| strategy.to_string() == s.to_ascii_lowercase())
+            .ok_or_else(|| format!("unknown strategy '{}', expected binary, skewed, random or linear", s))
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Strategy::Binary => "binary",
+            Strategy::Skewed => "skewed",
+            Strategy::Random => "random",
+            Strategy::Linear => "linear",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Keeps track of the numbers that are still possible and picks the next guess from them.
+pub struct Solver {
+    strategy: Strategy,
+    low: i64,
+    high: i64,
+    rng: StdRng,
+}
+
+impl Solver {
+    // The seed is only used by the random strategy.
+    pub fn new(strategy: Strategy, range: GameRange, seed: u64) -> Solver {
+        Solver {
+            strategy,
+            low: i64::from(range.low()),
+            high: i64::from(range.high()),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    // None once the feedback so far leaves no possible number, which means the feedback was inconsistent.
+    pub fn next_guess(&mut self) -> Option<i32> {
+        if self.low > self.high {
+            return None;
+        }
+
+        let width = self.high - self.low;
+        let guess = match self.strategy {
+            Strategy::Binary => self.low + width / 2,
+            Strategy::Skewed => self.low + width / 3,
+            Strategy::Random => self.rng.gen_range(self.low..=self.high),
+            Strategy::Linear => self.low,
+        };
+        // low and high always come from an i32 range, so anything between them fits.
+        Some(guess as i32)
+    }
+
+    // `ordering` is how the guess compares to the secret, as in `guess.cmp(&secret)`.
+    pub fn feedback(&mut self, guess: i32, ordering: Ordering) {
+        match ordering {
+            Ordering::Less => self.low = self.low.max(i64::from(guess) + 1),
+            Ordering::Greater => self.high = self.high.min(i64::from(guess) - 1),
+            Ordering::Equal => {
+                self.low = i64::from(guess);
+                self.high = i64::from(guess);
+            }
+        }
+    }
+
+    // The smallest and largest numbers that are still possible, or None if nothing is.
+    pub fn remaining(&self) -> Option<(i32, i32)> {
+        if self.low > self.high {
+            return None;
+        }
+        Some((self.low as i32, self.high as i32))
+    }
+}
+
+// Plays one game against `secret` and returns how many guesses it took.
+pub fn solve(strategy: Strategy, range: GameRange, secret: i32, seed: u64) -> u32 {
+    let mut solver = Solver::new(strategy, range, seed);
+    let mut guesses = 0;
+
+    while let Some(guess) = solver.next_guess() {
+        guesses += 1;
+        let ordering = guess.cmp(&secret);
+        if ordering == Ordering::Equal {
+            return guesses;
+        }
+        solver.feedback(guess, ordering);
+    }
+
+    panic!("secret {} is not between {}", secret, range);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_strategy_finds_every_secret() {
+        let range = GameRange::new(-5, 20).unwrap();
+        for strategy in Strategy::ALL {
+            for secret in -5..=20 {
+                let guesses = solve(strategy, range, secret, 1);
+                assert!((1..=26).contains(&guesses), "{} took {}", strategy, guesses);
+            }
+        }
+    }
+
+    #[test]
+    fn binary_search_stays_within_log2() {
+        let range = GameRange::default();
+        let worst = (1..=100)
+            .map(|secret| solve(Strategy::Binary, range, secret, 0))
+            .max();
+        assert_eq!(worst, Some(7));
+    }
+
+    #[test]
+    fn handles_the_whole_i32_range() {
+        let range = GameRange::new(i32::MIN, i32::MAX).unwrap();
+        assert_eq!(solve(Strategy::Binary, range, i32::MAX, 0), 33);
+        assert!(solve(Strategy::Binary, range, i32::MIN, 0) <= 33);
+    }
+
+    #[test]
+    fn contradictory_feedback_leaves_nothing() {
+        let mut solver = Solver::new(Strategy::Binary, GameRange::default(), 0);
+        solver.feedback(60, Ordering::Less);
+        solver.feedback(40, Ordering::Greater);
+        assert_eq!(solver.remaining(), None);
+        assert_eq!(solver.next_guess(), None);
+    }
+}
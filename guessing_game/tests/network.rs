@@ -0,0 +1,124 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use guessing_game::server::{self, MatchOutcome};
+use guessing_game::GameRange;
+
+fn start_server(secret: i32, players: usize) -> (SocketAddr, JoinHandle<MatchOutcome>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let handle = thread::spawn(move || {
+        server::host(&listener, GameRange::default(), secret, players).unwrap()
+    });
+    (address, handle)
+}
+
+// Connects and sends the whole script up front. The server only reads a player's line on their turn,
+// so the lines are consumed in turn order no matter how early they arrive.
+fn connect(address: SocketAddr, script: &str) -> TcpStream {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(script.as_bytes()).unwrap();
+    stream
+}
+
+fn transcript(mut stream: TcpStream) -> String {
+    let mut text = String::new();
+    stream.read_to_string(&mut text).unwrap();
+    text
+}
+
+#[test]
+fn players_take_turns_until_someone_wins() {
+    let (address, server) = start_server(30, 2);
+    let ana = connect(address, "ana\n50\n20\n");
+    let bo = connect(address, "bo\n200\n25\n30\n");
+
+    assert_eq!(
+        server.join().unwrap(),
+        MatchOutcome::Winner {
+            name: String::from("bo"),
+            guesses: 2
+        }
+    );
+
+    let ana = transcript(ana);
+    let bo = transcript(bo);
+    for text in [&ana, &bo] {
+        assert!(text.contains("Everyone is here. The number is between 1 and 100."));
+        assert!(text.contains("ana guessed 50: Too big!\n"));
+        assert!(text.contains("bo guessed 25: Too small!\n"));
+        assert!(text.contains("ana guessed 20: Too small!\n"));
+        assert!(text.ends_with("bo guessed 30: bo wins!\n"));
+    }
+    // Invalid guesses are only reported to the player who made them and do not use up the turn.
+    assert!(bo.contains("Guess value must be between 1 and 100, got 200."));
+    assert!(!ana.contains("got 200"));
+    assert!(ana.contains("Waiting for bo to guess."));
+}
+
+#[test]
+fn the_game_goes_on_when_a_player_leaves() {
+    let (address, server) = start_server(7, 2);
+    let ana = connect(address, "ana\n");
+    ana.shutdown(std::net::Shutdown::Write).unwrap();
+    let bo = connect(address, "ana\n1\n7\n");
+
+    assert_eq!(
+        server.join().unwrap(),
+        MatchOutcome::Winner {
+            name: String::from("ana2"),
+            guesses: 2
+        }
+    );
+    let bo = transcript(bo);
+    assert!(bo.contains("Hello ana2!"));
+    assert!(bo.contains("ana left the game."));
+    assert!(bo.ends_with("ana2 guessed 7: ana2 wins!\n"));
+}
+
+#[test]
+fn nobody_left_means_no_winner() {
+    let (address, server) = start_server(7, 1);
+    let ana = connect(address, "ana\n");
+    ana.shutdown(std::net::Shutdown::Write).unwrap();
+
+    assert_eq!(server.join().unwrap(), MatchOutcome::Abandoned);
+}
+
+#[test]
+fn silent_players_are_dropped() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let timeout = Duration::from_millis(200);
+        server::host_with_timeout(&listener, GameRange::default(), 7, 2, timeout).unwrap()
+    });
+
+    // The first client never says its name, the second names itself but never guesses.
+    let silent = connect(address, "");
+    let ana = connect(address, "ana\n");
+    let bo = connect(address, "bo\n7\n");
+
+    assert_eq!(
+        server.join().unwrap(),
+        MatchOutcome::Winner {
+            name: String::from("bo"),
+            guesses: 1
+        }
+    );
+    let bo = transcript(bo);
+    assert!(bo.contains("ana left the game."));
+    assert!(bo.ends_with("bo guessed 7: bo wins!\n"));
+    assert!(transcript(ana).contains("You took too long, goodbye."));
+    assert!(transcript(silent).contains("You took too long, goodbye."));
+}
+
+#[test]
+fn a_zero_timeout_is_refused_before_anyone_connects() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let error = server::host_with_timeout(&listener, GameRange::default(), 7, 1, Duration::ZERO)
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
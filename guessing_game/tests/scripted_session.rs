@@ -0,0 +1,92 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use guessing_game::cli::{self, Config};
+use guessing_game::{Difficulty, Game, Outcome};
+
+fn transcript(game: &Game, script: &str) -> (Outcome, String) {
+    let mut output = Vec::new();
+    let outcome = game.play(script.as_bytes(), &mut output).unwrap();
+    (outcome, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn replays_a_winning_session() {
+    let game = Game::new(Config::default(), 37);
+    let (outcome, output) = transcript(&game, "50\n\nabc\n25\n37\n");
+
+    assert_eq!(outcome, Outcome::Won { guesses: 3 });
+    assert_eq!(
+        output,
+        "\
+Guess the number!
+Difficulty: medium. The number is between 1 and 100, and you have 7 attempts.
+Please input your guess. (7 attempts left)
+You guessed: 50
+Too big!
+Please input your guess. (6 attempts left)
+Please type a number before pressing enter.
+Please input your guess. (6 attempts left)
+'abc' is not a whole number.
+Please input your guess. (6 attempts left)
+You guessed: 25
+Too small!
+Getting warmer!
+Please input your guess. (5 attempts left)
+You guessed: 37
+You win!
+"
+    );
+}
+
+#[test]
+fn runs_out_of_attempts() {
+    let game = Game::new(Config::from_difficulty(Difficulty::Easy), 1);
+    let script = "50\n".repeat(10);
+    let (outcome, output) = transcript(&game, &script);
+
+    assert_eq!(outcome, Outcome::Lost);
+    assert!(output.ends_with("You lose! The number was 1.\n"));
+}
+
+#[test]
+fn stops_when_input_runs_out() {
+    let game = Game::new(Config::default(), 5);
+    let (outcome, output) = transcript(&game, "1\n");
+
+    assert_eq!(outcome, Outcome::Abandoned);
+    assert!(output.ends_with("No more input, the number was 5.\n"));
+}
+
+#[test]
+fn same_seed_gives_same_secret() {
+    let seeded = |seed: &str| {
+        let args = ["--difficulty", "hard", "--seed", seed].map(String::from);
+        Game::from_config(cli::parse_args(args).unwrap()).secret()
+    };
+
+    assert_eq!(seeded("7"), seeded("7"));
+    assert!((1..=1000).contains(&seeded("7")));
+}
+
+#[test]
+fn binary_plays_a_seeded_session() {
+    let secret = Game::from_config(Config {
+        seed: Some(2024),
+        ..Config::default()
+    })
+    .secret();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .args(["--seed", "2024"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    writeln!(child.stdin.take().unwrap(), "{}", secret).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+    assert!(stdout.contains(&format!("You guessed: {}\nYou win!", secret)));
+}
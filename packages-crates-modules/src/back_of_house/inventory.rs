@@ -0,0 +1,476 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::season::Season;
+use crate::front_of_house::serving::{Order, OrderItem};
+
+// Whole units of whatever the ingredient is counted in: grams, millilitres, slices or pieces.
+pub type Quantity = u32;
+
+// The choice on an order item naming the fruit for a recipe with seasonal_fruit, see Breakfast::order_item.
+pub const FRUIT_MODIFIER: &str = "fruit";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Portion {
+    pub ingredient: String,
+    pub quantity: Quantity,
+}
+
+// Extra ingredients for one option of a modifier, like the bread for rye toast.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChoicePortions {
+    pub modifier: String,
+    pub option: String,
+    pub ingredients: Vec<Portion>,
+}
+
+// What goes into one of a menu item.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Recipe {
+    pub item: String,
+    #[serde(default)]
+    pub ingredients: Vec<Portion>,
+    #[serde(default)]
+    pub choices: Vec<ChoicePortions>,
+    // How much fruit goes on the plate. The fruit is the item's FRUIT_MODIFIER choice, or what is in season without one.
+    #[serde(default)]
+    pub seasonal_fruit: Quantity,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StockLevel {
+    pub ingredient: String,
+    pub unit: String,
+    pub on_hand: Quantity,
+    // At or below this the ingredient shows up on the reorder report.
+    pub reorder_level: Quantity,
+    pub reorder_quantity: Quantity,
+}
+
+impl StockLevel {
+    pub fn is_low(&self) -> bool {
+        self.on_hand <= self.reorder_level
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortage {
+    pub ingredient: String,
+    pub needed: Quantity,
+    pub on_hand: Quantity,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InventoryError {
+    Parse(String),
+    DuplicateIngredient(String),
+    UnknownIngredient { recipe: String, ingredient: String },
+    MissingRecipe(String),
+    // An order so large the amount of an ingredient does not fit in a Quantity.
+    TooMuch { item: String, quantity: u32 },
+    // Everything the order needs more of than the kitchen has, not just the first thing that ran out.
+    Shortages(Vec<Shortage>),
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InventoryError::Parse(message) => write!(f, "could not parse the pantry: {}", message),
+            InventoryError::DuplicateIngredient(ingredient) => {
+                write!(f, "{} is stocked twice", ingredient)
+            }
+            InventoryError::UnknownIngredient { recipe, ingredient } => {
+                write!(f, "{} needs {}, which is not stocked", recipe, ingredient)
+            }
+            InventoryError::MissingRecipe(item) => write!(f, "there is no recipe for {}", item),
+            InventoryError::TooMuch { item, quantity } => {
+                write!(
+                    f,
+                    "{} x {} is more than the pantry can count",
+                    quantity, item
+                )
+            }
+            InventoryError::Shortages(shortages) => {
+                write!(f, "not enough")?;
+                for (index, shortage) in shortages.iter().enumerate() {
+                    let separator = if index == 0 { " " } else { ", " };
+                    write!(
+                        f,
+                        "{}{} (need {}, have {})",
+                        separator, shortage.ingredient, shortage.needed, shortage.on_hand
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for InventoryError {}
+
+// An ingredient that is running low and how much to order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reorder {
+    pub ingredient: String,
+    pub unit: String,
+    pub on_hand: Quantity,
+    pub order: Quantity,
+}
+
+impl fmt::Display for Reorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:<16} {:>6} {:<6} order {}",
+            self.ingredient, self.on_hand, self.unit, self.order
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Pantry {
+    stock: Vec<StockLevel>,
+    recipes: Vec<Recipe>,
+}
+
+// The ingredients on the shelves and the recipes that use them up.
+#[derive(Debug, Clone)]
+pub struct Inventory {
+    stock: BTreeMap<String, StockLevel>,
+    recipes: BTreeMap<String, Recipe>,
+    season: Season,
+}
+
+impl Inventory {
+    // The stock and recipes in pantry.toml at the root of the crate, for the house menu.
+    pub fn house() -> Inventory {
+        Inventory::from_toml(include_str!("../../pantry.toml")).expect("pantry.toml is valid")
+    }
+
+    // Every ingredient a recipe uses has to be stocked, even if there is none of it left.
+    pub fn from_toml(text: &str) -> Result<Inventory, InventoryError> {
+        let pantry: Pantry =
+            toml::from_str(text).map_err(|error| InventoryError::Parse(error.to_string()))?;
+
+        let mut stock = BTreeMap::new();
+        for level in pantry.stock {
+            if stock.contains_key(&level.ingredient) {
+                return Err(InventoryError::DuplicateIngredient(level.ingredient));
+            }
+            stock.insert(level.ingredient.clone(), level);
+        }
+
+        let fruit = [
+            Season::Spring,
+            Season::Summer,
+            Season::Autumn,
+            Season::Winter,
+        ]
+        .map(|season| season.fruit());
+        for recipe in &pantry.recipes {
+            let mut used: Vec<&str> = recipe
+                .ingredients
+                .iter()
+                .chain(recipe.choices.iter().flat_map(|choice| &choice.ingredients))
+                .map(|portion| portion.ingredient.as_str())
+                .collect();
+            if recipe.seasonal_fruit > 0 {
+                used.extend(fruit);
+            }
+            if let Some(missing) = used.into_iter().find(|&name| !stock.contains_key(name)) {
+                return Err(InventoryError::UnknownIngredient {
+                    recipe: recipe.item.clone(),
+                    ingredient: String::from(missing),
+                });
+            }
+        }
+
+        let recipes = pantry
+            .recipes
+            .into_iter()
+            .map(|recipe| (recipe.item.clone(), recipe))
+            .collect();
+        Ok(Inventory {
+            stock,
+            recipes,
+            season: Season::Summer,
+        })
+    }
+
+    pub fn season(&self) -> Season {
+        self.season
+    }
+
+    // Which fruit recipes with seasonal_fruit take from, for items that do not choose one.
+    pub fn set_season(&mut self, season: Season) {
+        self.season = season;
+    }
+
+    pub fn stock(&self, ingredient: &str) -> Option<&StockLevel> {
+        self.stock.get(ingredient)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StockLevel> {
+        self.stock.values()
+    }
+
+    pub fn recipe(&self, item: &str) -> Option<&Recipe> {
+        self.recipes.get(item)
+    }
+
+    // A delivery came in. Returns how much of the ingredient there is now, None if it is not stocked
+    // or there would be more than a Quantity can count, in which case the stock is left as it was.
+    pub fn restock(&mut self, ingredient: &str, quantity: Quantity) -> Option<Quantity> {
+        let level = self.stock.get_mut(ingredient)?;
+        level.on_hand = level.on_hand.checked_add(quantity)?;
+        Some(level.on_hand)
+    }
+
+    // The total of every ingredient needed to make `items`.
+    pub fn needs(&self, items: &[OrderItem]) -> Result<BTreeMap<String, Quantity>, InventoryError> {
+        let mut needs: BTreeMap<String, Quantity> = BTreeMap::new();
+        for item in items {
+            let recipe = self
+                .recipes
+                .get(&item.name)
+                .ok_or_else(|| InventoryError::MissingRecipe(item.name.clone()))?;
+
+            let chosen = recipe.choices.iter().filter(|portions| {
+                item.choice(&portions.modifier) == Some(portions.option.as_str())
+            });
+            let mut portions: Vec<(&str, Quantity)> = recipe
+                .ingredients
+                .iter()
+                .chain(chosen.flat_map(|portions| &portions.ingredients))
+                .map(|portion| (portion.ingredient.as_str(), portion.quantity))
+                .collect();
+            if recipe.seasonal_fruit > 0 {
+                let fruit = item
+                    .choice(FRUIT_MODIFIER)
+                    .unwrap_or_else(|| self.season.fruit());
+                portions.push((fruit, recipe.seasonal_fruit));
+            }
+
+            let too_much = || InventoryError::TooMuch {
+                item: item.name.clone(),
+                quantity: item.quantity,
+            };
+            for (ingredient, quantity) in portions {
+                let total = needs.entry(String::from(ingredient)).or_insert(0);
+                *total = quantity
+                    .checked_mul(item.quantity)
+                    .and_then(|needed| total.checked_add(needed))
+                    .ok_or_else(too_much)?;
+            }
+        }
+        Ok(needs)
+    }
+
+    // What cooking `order` would use, or every shortage that stands in the way.
+    pub fn check(&self, order: &Order) -> Result<BTreeMap<String, Quantity>, InventoryError> {
+        let needs = self.needs(order.items())?;
+        let shortages: Vec<_> = needs
+            .iter()
+            .filter_map(|(ingredient, &needed)| {
+                let on_hand = self.stock.get(ingredient).map_or(0, |level| level.on_hand);
+                (needed > on_hand).then(|| Shortage {
+                    ingredient: ingredient.clone(),
+                    needed,
+                    on_hand,
+                })
+            })
+            .collect();
+
+        if shortages.is_empty() {
+            Ok(needs)
+        } else {
+            Err(InventoryError::Shortages(shortages))
+        }
+    }
+
+    // Takes what check returned off the shelves.
+    pub fn take(&mut self, needs: &BTreeMap<String, Quantity>) {
+        for (ingredient, &quantity) in needs {
+            if let Some(level) = self.stock.get_mut(ingredient) {
+                level.on_hand = level.on_hand.saturating_sub(quantity);
+            }
+        }
+    }
+
+    // Uses up the ingredients for `order`. Nothing is taken if anything is short.
+    pub fn consume(&mut self, order: &Order) -> Result<(), InventoryError> {
+        let needs = self.check(order)?;
+        self.take(&needs);
+        Ok(())
+    }
+
+    // Everything at or below its reorder level, with enough on order to get back above it.
+    pub fn reorder_report(&self) -> Vec<Reorder> {
+        self.stock
+            .values()
+            .filter(|level| level.is_low())
+            .map(|level| Reorder {
+                ingredient: level.ingredient.clone(),
+                unit: level.unit.clone(),
+                on_hand: level.on_hand,
+                order: level
+                    .reorder_quantity
+                    .max(level.reorder_level + 1 - level.on_hand),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::front_of_house::serving::{take_order, OrderBook, OrderStatus};
+
+    fn order(items: Vec<OrderItem>) -> Order {
+        let mut book = OrderBook::new();
+        let id = take_order(&mut book, 1, items).unwrap();
+        book.get(id).unwrap().clone()
+    }
+
+    #[test]
+    fn needs_follow_choices_and_season() {
+        let mut inventory = Inventory::house();
+        let breakfast = OrderItem::new("Breakfast", 2).with_choice("toast", "Rye");
+
+        let needs = inventory.needs(std::slice::from_ref(&breakfast)).unwrap();
+        assert_eq!(needs["eggs"], 4);
+        assert_eq!(needs["rye bread"], 4);
+        assert_eq!(needs["peaches"], 200);
+        assert!(!needs.contains_key("wheat bread"));
+
+        inventory.set_season(Season::Winter);
+        assert_eq!(inventory.needs(&[breakfast]).unwrap()["oranges"], 200);
+        assert_eq!(
+            inventory.needs(&[OrderItem::new("Cake", 1)]),
+            Err(InventoryError::MissingRecipe(String::from("Cake")))
+        );
+    }
+
+    #[test]
+    fn breakfast_takes_the_fruit_it_was_made_with() {
+        let inventory = Inventory::house();
+        assert_eq!(inventory.season(), Season::Summer);
+
+        let winter = crate::Breakfast::in_season("Wheat", Season::Winter).order_item();
+        let needs = inventory.needs(&[winter]).unwrap();
+        assert_eq!(needs["oranges"], 100);
+        assert!(!needs.contains_key("peaches"));
+
+        // The menu accepts the fruit, so the breakfast can still be billed.
+        let menu = crate::menu::Menu::house();
+        let spring = crate::Breakfast::in_season("Rye", Season::Spring).order_item();
+        assert_eq!(menu.unit_price(&spring).unwrap(), 1250);
+    }
+
+    #[test]
+    fn huge_orders_are_refused_not_overflowed() {
+        let inventory = Inventory::house();
+        let huge = OrderItem::new("Soup", u32::MAX);
+        assert_eq!(
+            inventory.needs(&[huge]),
+            Err(InventoryError::TooMuch {
+                item: String::from("Soup"),
+                quantity: u32::MAX
+            })
+        );
+        // Each line fits on its own but the total does not.
+        let half = OrderItem::new("Pie", u32::MAX / 2 + 1);
+        assert!(matches!(
+            inventory.needs(&[half.clone(), half]),
+            Err(InventoryError::TooMuch { .. })
+        ));
+    }
+
+    #[test]
+    fn shortages_refuse_the_whole_order() {
+        let mut inventory = Inventory::house();
+        let pies = order(vec![OrderItem::new("Pie", 10), OrderItem::new("Coffee", 1)]);
+        inventory.consume(&pies).unwrap();
+        assert_eq!(inventory.stock("pie").unwrap().on_hand, 6);
+
+        let more = order(vec![OrderItem::new("Pie", 7), OrderItem::new("Coffee", 1)]);
+        assert_eq!(
+            inventory.consume(&more),
+            Err(InventoryError::Shortages(vec![Shortage {
+                ingredient: String::from("pie"),
+                needed: 7,
+                on_hand: 6
+            }]))
+        );
+        assert_eq!(inventory.stock("coffee beans").unwrap().on_hand, 982);
+    }
+
+    #[test]
+    fn reports_low_stock() {
+        let mut inventory = Inventory::house();
+        assert!(inventory.reorder_report().is_empty());
+
+        inventory
+            .consume(&order(vec![OrderItem::new("Pie", 13)]))
+            .unwrap();
+        let report = inventory.reorder_report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].order, 16);
+        assert_eq!(
+            report[0].to_string(),
+            "pie                   3 slices order 16"
+        );
+
+        assert_eq!(inventory.restock("pie", 16), Some(19));
+        assert!(inventory.reorder_report().is_empty());
+        assert_eq!(inventory.restock("caviar", 1), None);
+        assert_eq!(inventory.restock("pie", Quantity::MAX), None);
+        assert_eq!(inventory.restock("pie", 0), Some(19));
+    }
+
+    #[test]
+    fn cook_order_takes_stock_or_refuses() {
+        let menu = crate::menu::Menu::house();
+        let mut kitchen = crate::kitchen::Kitchen::with_inventory(Inventory::house());
+        let mut book = OrderBook::new();
+        let soup = take_order(&mut book, 1, vec![OrderItem::new("Soup", 20)]).unwrap();
+        let more = take_order(&mut book, 2, vec![OrderItem::new("Soup", 1)]).unwrap();
+
+        crate::cook_order(&mut kitchen, &menu, book.get_mut(soup).unwrap()).unwrap();
+        let stock = kitchen
+            .inventory()
+            .unwrap()
+            .stock("vegetable stock")
+            .unwrap();
+        assert_eq!(stock.on_hand, 0);
+
+        let refused = crate::cook_order(&mut kitchen, &menu, book.get_mut(more).unwrap());
+        assert!(matches!(
+            refused,
+            Err(crate::kitchen::KitchenError::Inventory(
+                InventoryError::Shortages(_)
+            ))
+        ));
+        assert_eq!(book.get(more).unwrap().status(), OrderStatus::Placed);
+        assert_eq!(kitchen.run_until_idle(), vec![soup]);
+    }
+
+    #[test]
+    fn recipes_must_use_stocked_ingredients() {
+        let text = r#"
+            stock = []
+
+            [[recipes]]
+            item = "Toast"
+            ingredients = [{ ingredient = "bread", quantity = 1 }]
+        "#;
+        assert_eq!(
+            Inventory::from_toml(text).unwrap_err(),
+            InventoryError::UnknownIngredient {
+                recipe: String::from("Toast"),
+                ingredient: String::from("bread")
+            }
+        );
+    }
+}
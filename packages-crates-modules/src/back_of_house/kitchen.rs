@@ -0,0 +1,469 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+
+use crate::back_of_house::inventory::{Inventory, InventoryError};
+use crate::back_of_house::menu::{Menu, MenuError};
+use crate::front_of_house::serving::{Order, OrderError, OrderId};
+
+// Simulated time in minutes since the kitchen opened. Nothing here ever looks at the real clock.
+pub type Minutes = u32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Station {
+    Grill,
+    Stove,
+    Cold,
+    Pastry,
+    Bar,
+}
+
+impl Station {
+    pub const ALL: [Station; 5] = [
+        Station::Grill,
+        Station::Stove,
+        Station::Cold,
+        Station::Pastry,
+        Station::Bar,
+    ];
+
+    // Used for menu items that do not give their own prep time.
+    pub fn default_prep_minutes(&self) -> Minutes {
+        match self {
+            Station::Grill => 12,
+            Station::Stove => 8,
+            Station::Cold => 5,
+            Station::Pastry => 4,
+            Station::Bar => 2,
+        }
+    }
+}
+
+impl fmt::Display for Station {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Station::Grill => "grill",
+            Station::Stove => "stove",
+            Station::Cold => "cold",
+            Station::Pastry => "pastry",
+            Station::Bar => "bar",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug)]
+pub enum KitchenError {
+    Order(OrderError),
+    Menu(MenuError),
+    Inventory(InventoryError),
+}
+
+impl fmt::Display for KitchenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KitchenError::Order(error) => write!(f, "{}", error),
+            KitchenError::Menu(error) => write!(f, "{}", error),
+            KitchenError::Inventory(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for KitchenError {}
+
+impl From<OrderError> for KitchenError {
+    fn from(error: OrderError) -> KitchenError {
+        KitchenError::Order(error)
+    }
+}
+
+impl From<MenuError> for KitchenError {
+    fn from(error: MenuError) -> KitchenError {
+        KitchenError::Menu(error)
+    }
+}
+
+impl From<InventoryError> for KitchenError {
+    fn from(error: InventoryError) -> KitchenError {
+        KitchenError::Inventory(error)
+    }
+}
+
+// Remakes jump the queue, a guest is already waiting on them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    Remake,
+    Normal,
+}
+
+// One line of an order, cooked in one batch at one station.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ticket {
+    pub order: OrderId,
+    pub item: String,
+    pub quantity: u32,
+    pub station: Station,
+    pub prep_minutes: Minutes,
+    pub priority: Priority,
+    pub queued_at: Minutes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedTicket {
+    pub ticket: Ticket,
+    pub started_at: Minutes,
+    pub finished_at: Minutes,
+}
+
+impl CompletedTicket {
+    // Minutes the ticket sat in the queue before a cook picked it up.
+    pub fn wait(&self) -> Minutes {
+        self.started_at - self.ticket.queued_at
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KitchenStats {
+    pub completed_tickets: usize,
+    pub completed_orders: usize,
+    pub average_wait_minutes: f64,
+    pub average_ticket_minutes: f64,
+    pub tickets_per_hour: f64,
+}
+
+#[derive(Debug, Default)]
+struct StationQueue {
+    cooks: usize,
+    remakes: VecDeque<Ticket>,
+    normal: VecDeque<Ticket>,
+    // The ticket each busy cook is working on, when they started it and when they will be done.
+    in_progress: Vec<(Ticket, Minutes, Minutes)>,
+}
+
+impl StationQueue {
+    fn next_ticket(&mut self) -> Option<Ticket> {
+        self.remakes.pop_front().or_else(|| self.normal.pop_front())
+    }
+}
+
+// The kitchen display: queues of tickets per station, worked through by the cooks as simulated time passes.
+#[derive(Debug)]
+pub struct Kitchen {
+    now: Minutes,
+    stations: BTreeMap<Station, StationQueue>,
+    outstanding: HashMap<OrderId, usize>,
+    completed: Vec<CompletedTicket>,
+    completed_orders: usize,
+    // Stock is only tracked when the kitchen was given an inventory.
+    inventory: Option<Inventory>,
+}
+
+impl Kitchen {
+    // One cook on every station.
+    pub fn new() -> Kitchen {
+        let stations = Station::ALL
+            .iter()
+            .map(|&station| {
+                let queue = StationQueue {
+                    cooks: 1,
+                    ..StationQueue::default()
+                };
+                (station, queue)
+            })
+            .collect();
+
+        Kitchen {
+            now: 0,
+            stations,
+            outstanding: HashMap::new(),
+            completed: Vec::new(),
+            completed_orders: 0,
+            inventory: None,
+        }
+    }
+
+    // A kitchen that uses up `inventory` as it cooks, see cook_order.
+    pub fn with_inventory(inventory: Inventory) -> Kitchen {
+        Kitchen {
+            inventory: Some(inventory),
+            ..Kitchen::new()
+        }
+    }
+
+    pub fn inventory(&self) -> Option<&Inventory> {
+        self.inventory.as_ref()
+    }
+
+    pub fn inventory_mut(&mut self) -> Option<&mut Inventory> {
+        self.inventory.as_mut()
+    }
+
+    pub fn set_cooks(&mut self, station: Station, cooks: usize) {
+        self.station_mut(station).cooks = cooks;
+        self.start_free_cooks();
+    }
+
+    pub fn now(&self) -> Minutes {
+        self.now
+    }
+
+    // Queues a ticket for every line of `order`. Every item is checked against the menu first,
+    // so an order with an unknown item adds nothing.
+    pub fn enqueue(
+        &mut self,
+        order: &Order,
+        menu: &Menu,
+        priority: Priority,
+    ) -> Result<(), MenuError> {
+        let mut tickets = Vec::new();
+        for line in order.items() {
+            let item = menu
+                .item(&line.name)
+                .ok_or_else(|| MenuError::UnknownItem(line.name.clone()))?;
+            tickets.push(Ticket {
+                order: order.id(),
+                item: line.name.clone(),
+                quantity: line.quantity,
+                station: item.station(),
+                prep_minutes: item.prep_minutes(),
+                priority,
+                queued_at: self.now,
+            });
+        }
+
+        *self.outstanding.entry(order.id()).or_default() += tickets.len();
+        for ticket in tickets {
+            let queue = self.station_mut(ticket.station);
+            match ticket.priority {
+                Priority::Remake => queue.remakes.push_back(ticket),
+                Priority::Normal => queue.normal.push_back(ticket),
+            }
+        }
+        self.start_free_cooks();
+        Ok(())
+    }
+
+    // Tickets not yet picked up by a cook, in the order they will be cooked.
+    pub fn queue(&self, station: Station) -> Vec<&Ticket> {
+        let queue = &self.stations[&station];
+        queue.remakes.iter().chain(queue.normal.iter()).collect()
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.outstanding.is_empty()
+    }
+
+    // When the next ticket being cooked will be done, None if every cook is idle.
+    pub fn next_finish(&self) -> Option<Minutes> {
+        self.stations
+            .values()
+            .flat_map(|queue| queue.in_progress.iter().map(|&(_, _, done)| done))
+            .min()
+    }
+
+    // Moves the clock forward to `time`, finishing and starting tickets along the way.
+    // Returns the orders whose last ticket finished, in the order they finished.
+    pub fn run_until(&mut self, time: Minutes) -> Vec<OrderId> {
+        let mut ready = Vec::new();
+        while let Some(next) = self.next_finish().filter(|&next| next <= time) {
+            self.now = next;
+            ready.extend(self.finish_due());
+            self.start_free_cooks();
+        }
+        self.now = self.now.max(time);
+        ready
+    }
+
+    // Runs until every ticket a cook can pick up is cooked.
+    pub fn run_until_idle(&mut self) -> Vec<OrderId> {
+        let mut ready = Vec::new();
+        while let Some(next) = self.next_finish() {
+            ready.extend(self.run_until(next));
+        }
+        ready
+    }
+
+    pub fn completed(&self) -> &[CompletedTicket] {
+        &self.completed
+    }
+
+    pub fn stats(&self) -> KitchenStats {
+        let count = self.completed.len();
+        let average = |total: Minutes| {
+            if count == 0 {
+                0.0
+            } else {
+                f64::from(total) / count as f64
+            }
+        };
+        let total_wait = self.completed.iter().map(CompletedTicket::wait).sum();
+        let total_time = self
+            .completed
+            .iter()
+            .map(|done| done.finished_at - done.ticket.queued_at)
+            .sum();
+
+        KitchenStats {
+            completed_tickets: count,
+            completed_orders: self.completed_orders,
+            average_wait_minutes: average(total_wait),
+            average_ticket_minutes: average(total_time),
+            tickets_per_hour: if self.now == 0 {
+                0.0
+            } else {
+                count as f64 * 60.0 / f64::from(self.now)
+            },
+        }
+    }
+
+    fn station_mut(&mut self, station: Station) -> &mut StationQueue {
+        self.stations.entry(station).or_default()
+    }
+
+    fn start_free_cooks(&mut self) {
+        let now = self.now;
+        for queue in self.stations.values_mut() {
+            while queue.in_progress.len() < queue.cooks {
+                let Some(ticket) = queue.next_ticket() else {
+                    break;
+                };
+                let done = now + ticket.prep_minutes;
+                queue.in_progress.push((ticket, now, done));
+            }
+        }
+    }
+
+    fn finish_due(&mut self) -> Vec<OrderId> {
+        let now = self.now;
+        let mut ready = Vec::new();
+        for queue in self.stations.values_mut() {
+            let (due, busy): (Vec<_>, Vec<_>) = queue
+                .in_progress
+                .drain(..)
+                .partition(|&(_, _, done)| done <= now);
+            queue.in_progress = busy;
+
+            for (ticket, started_at, finished_at) in due {
+                let left = self
+                    .outstanding
+                    .get_mut(&ticket.order)
+                    .expect("every queued ticket is counted");
+                *left -= 1;
+                if *left == 0 {
+                    self.outstanding.remove(&ticket.order);
+                    self.completed_orders += 1;
+                    ready.push(ticket.order);
+                }
+                self.completed.push(CompletedTicket {
+                    ticket,
+                    started_at,
+                    finished_at,
+                });
+            }
+        }
+        ready
+    }
+}
+
+impl Default for Kitchen {
+    fn default() -> Kitchen {
+        Kitchen::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::front_of_house::serving::{take_order, OrderBook, OrderItem};
+
+    fn order(book: &mut OrderBook, items: Vec<OrderItem>) -> Order {
+        let id = take_order(book, 1, items).unwrap();
+        book.get(id).unwrap().clone()
+    }
+
+    #[test]
+    fn cooks_tickets_in_simulated_time() {
+        let menu = Menu::house();
+        let mut book = OrderBook::new();
+        let mut kitchen = Kitchen::new();
+
+        let burger = OrderItem::new("Burger", 1).with_choice("side", "Fries");
+        let first = order(&mut book, vec![burger, OrderItem::new("Coffee", 1)]);
+        kitchen.enqueue(&first, &menu, Priority::Normal).unwrap();
+
+        assert_eq!(kitchen.run_until(5), Vec::<OrderId>::new());
+        assert_eq!(kitchen.now(), 5);
+        assert_eq!(kitchen.run_until(30), vec![first.id()]);
+        assert_eq!(kitchen.now(), 30);
+        assert!(kitchen.is_idle());
+    }
+
+    #[test]
+    fn remakes_go_first_and_fifo_within_priority() {
+        let menu = Menu::house();
+        let mut book = OrderBook::new();
+        let mut kitchen = Kitchen::new();
+
+        let orders: Vec<_> = (0..3)
+            .map(|_| order(&mut book, vec![OrderItem::new("Soup", 1)]))
+            .collect();
+        kitchen
+            .enqueue(&orders[0], &menu, Priority::Normal)
+            .unwrap();
+        kitchen
+            .enqueue(&orders[1], &menu, Priority::Normal)
+            .unwrap();
+        kitchen
+            .enqueue(&orders[2], &menu, Priority::Remake)
+            .unwrap();
+
+        // Order 1 is already on the stove, the remake goes ahead of order 2.
+        let waiting: Vec<_> = kitchen
+            .queue(Station::Stove)
+            .iter()
+            .map(|ticket| ticket.order)
+            .collect();
+        assert_eq!(waiting, vec![orders[2].id(), orders[1].id()]);
+        assert_eq!(
+            kitchen.run_until_idle(),
+            vec![orders[0].id(), orders[2].id(), orders[1].id()]
+        );
+    }
+
+    #[test]
+    fn more_cooks_mean_shorter_waits() {
+        let menu = Menu::house();
+        let stats_with = |cooks| {
+            let mut book = OrderBook::new();
+            let mut kitchen = Kitchen::new();
+            kitchen.set_cooks(Station::Stove, cooks);
+            for _ in 0..6 {
+                let soup = order(&mut book, vec![OrderItem::new("Soup", 1)]);
+                kitchen.enqueue(&soup, &menu, Priority::Normal).unwrap();
+            }
+            kitchen.run_until_idle();
+            kitchen.stats()
+        };
+
+        let one = stats_with(1);
+        let three = stats_with(3);
+        assert_eq!(one.completed_tickets, 6);
+        // One cook: waits of 0, 8, 16, 24, 32 and 40 minutes.
+        assert_eq!(one.average_wait_minutes, 20.0);
+        assert_eq!(three.average_wait_minutes, 4.0);
+        assert!(three.tickets_per_hour > one.tickets_per_hour);
+    }
+
+    #[test]
+    fn unknown_items_queue_nothing() {
+        let mut book = OrderBook::new();
+        let mut kitchen = Kitchen::new();
+        let cake = order(&mut book, vec![OrderItem::new("Cake", 1)]);
+
+        assert!(kitchen
+            .enqueue(&cake, &Menu::house(), Priority::Normal)
+            .is_err());
+        assert!(kitchen.is_idle());
+    }
+}
@@ -0,0 +1,315 @@
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::back_of_house::kitchen::{Minutes, Station};
+use crate::front_of_house::serving::OrderItem;
+
+// Money is counted in whole cents so that adding prices up never drifts the way floats do.
+pub type Cents = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Appetizer,
+    Breakfast,
+    Main,
+    Dessert,
+    Drink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ModifierOption {
+    pub name: String,
+    // What the option adds to the item's price, most options are free.
+    #[serde(default)]
+    pub price_cents: Cents,
+}
+
+// A choice the guest makes about an item, like which toast comes with the breakfast.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Modifier {
+    pub name: String,
+    #[serde(default)]
+    pub required: bool,
+    pub options: Vec<ModifierOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MenuItem {
+    pub name: String,
+    pub category: Category,
+    pub price_cents: Cents,
+    #[serde(default)]
+    pub modifiers: Vec<Modifier>,
+    // Where the item is cooked and how long it takes. Both fall back to defaults for the category.
+    #[serde(default)]
+    pub station: Option<Station>,
+    #[serde(default)]
+    pub prep_minutes: Option<Minutes>,
+}
+
+impl MenuItem {
+    pub fn station(&self) -> Station {
+        self.station.unwrap_or(match self.category {
+            Category::Appetizer => Station::Cold,
+            Category::Breakfast => Station::Stove,
+            Category::Main => Station::Grill,
+            Category::Dessert => Station::Pastry,
+            Category::Drink => Station::Bar,
+        })
+    }
+
+    pub fn prep_minutes(&self) -> Minutes {
+        self.prep_minutes
+            .unwrap_or_else(|| self.station().default_prep_minutes())
+    }
+
+    pub fn modifier(&self, name: &str) -> Option<&Modifier> {
+        self.modifiers.iter().find(|modifier| modifier.name == name)
+    }
+}
+
+#[derive(Debug)]
+pub enum MenuError {
+    Io(std::io::Error),
+    Parse(String),
+    UnsupportedFormat(String),
+    DuplicateItem(String),
+    EmptyModifier {
+        item: String,
+        modifier: String,
+    },
+    UnknownItem(String),
+    UnknownModifier {
+        item: String,
+        modifier: String,
+    },
+    UnknownOption {
+        item: String,
+        modifier: String,
+        option: String,
+    },
+    MissingChoice {
+        item: String,
+        modifier: String,
+    },
+}
+
+impl fmt::Display for MenuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MenuError::Io(error) => write!(f, "could not read the menu: {}", error),
+            MenuError::Parse(message) => write!(f, "could not parse the menu: {}", message),
+            MenuError::UnsupportedFormat(path) => {
+                write!(f, "{} is not a .toml or .json file", path)
+            }
+            MenuError::DuplicateItem(item) => write!(f, "{} is on the menu twice", item),
+            MenuError::EmptyModifier { item, modifier } => {
+                write!(f, "{} on {} has no options", modifier, item)
+            }
+            MenuError::UnknownItem(item) => write!(f, "{} is not on the menu", item),
+            MenuError::UnknownModifier { item, modifier } => {
+                write!(f, "{} does not come with a choice of {}", item, modifier)
+            }
+            MenuError::UnknownOption {
+                item,
+                modifier,
+                option,
+            } => {
+                write!(f, "{} is not a {} option for {}", option, modifier, item)
+            }
+            MenuError::MissingChoice { item, modifier } => {
+                write!(f, "{} needs a choice of {}", item, modifier)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MenuError {}
+
+impl From<std::io::Error> for MenuError {
+    fn from(error: std::io::Error) -> MenuError {
+        MenuError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Menu {
+    items: Vec<MenuItem>,
+}
+
+impl Menu {
+    // The menu in menu.toml at the root of the crate, compiled into the binary.
+    pub fn house() -> Menu {
+        Menu::from_toml(include_str!("../../menu.toml")).expect("menu.toml is a valid menu")
+    }
+
+    pub fn from_toml(text: &str) -> Result<Menu, MenuError> {
+        let menu: Menu =
+            toml::from_str(text).map_err(|error| MenuError::Parse(error.to_string()))?;
+        menu.validate()
+    }
+
+    pub fn from_json(text: &str) -> Result<Menu, MenuError> {
+        let menu: Menu =
+            serde_json::from_str(text).map_err(|error| MenuError::Parse(error.to_string()))?;
+        menu.validate()
+    }
+
+    // Picks the format from the file extension.
+    pub fn load(path: &Path) -> Result<Menu, MenuError> {
+        let text = fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Menu::from_toml(&text),
+            Some("json") => Menu::from_json(&text),
+            _ => Err(MenuError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+
+    fn validate(self) -> Result<Menu, MenuError> {
+        let mut names = HashSet::new();
+        for item in &self.items {
+            if !names.insert(item.name.as_str()) {
+                return Err(MenuError::DuplicateItem(item.name.clone()));
+            }
+            if let Some(modifier) = item.modifiers.iter().find(|m| m.options.is_empty()) {
+                return Err(MenuError::EmptyModifier {
+                    item: item.name.clone(),
+                    modifier: modifier.name.clone(),
+                });
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn items(&self) -> &[MenuItem] {
+        &self.items
+    }
+
+    pub fn item(&self, name: &str) -> Option<&MenuItem> {
+        self.items.iter().find(|item| item.name == name)
+    }
+
+    pub fn in_category(&self, category: Category) -> impl Iterator<Item = &MenuItem> {
+        self.items
+            .iter()
+            .filter(move |item| item.category == category)
+    }
+
+    // The price of one of `order_item`, including its modifiers. Fails if the item or one of its choices
+    // is not on the menu, or if a required choice was not made.
+    pub fn unit_price(&self, order_item: &OrderItem) -> Result<Cents, MenuError> {
+        let item = self
+            .item(&order_item.name)
+            .ok_or_else(|| MenuError::UnknownItem(order_item.name.clone()))?;
+
+        let mut price = item.price_cents;
+        for (modifier_name, option_name) in &order_item.choices {
+            let modifier =
+                item.modifier(modifier_name)
+                    .ok_or_else(|| MenuError::UnknownModifier {
+                        item: item.name.clone(),
+                        modifier: modifier_name.clone(),
+                    })?;
+            let option = modifier
+                .options
+                .iter()
+                .find(|option| &option.name == option_name)
+                .ok_or_else(|| MenuError::UnknownOption {
+                    item: item.name.clone(),
+                    modifier: modifier_name.clone(),
+                    option: option_name.clone(),
+                })?;
+            price += option.price_cents;
+        }
+
+        if let Some(missing) = item
+            .modifiers
+            .iter()
+            .find(|modifier| modifier.required && order_item.choice(&modifier.name).is_none())
+        {
+            return Err(MenuError::MissingChoice {
+                item: item.name.clone(),
+                modifier: missing.name.clone(),
+            });
+        }
+
+        Ok(price)
+    }
+
+    // unit_price times the quantity.
+    pub fn line_price(&self, order_item: &OrderItem) -> Result<Cents, MenuError> {
+        Ok(self.unit_price(order_item)? * Cents::from(order_item.quantity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn house_menu_loads() {
+        let menu = Menu::house();
+        assert_eq!(menu.in_category(Category::Appetizer).count(), 2);
+        assert_eq!(menu.item("Breakfast").unwrap().price_cents, 1250);
+        assert_eq!(menu.item("Soup").unwrap().station(), Station::Stove);
+        assert_eq!(menu.item("Pie").unwrap().prep_minutes(), 4);
+    }
+
+    #[test]
+    fn prices_include_modifiers() {
+        let menu = Menu::house();
+        let breakfast = OrderItem::new("Breakfast", 2).with_choice("toast", "Sourdough");
+
+        assert_eq!(menu.unit_price(&breakfast).unwrap(), 1300);
+        assert_eq!(menu.line_price(&breakfast).unwrap(), 2600);
+    }
+
+    #[test]
+    fn rejects_bad_choices() {
+        let menu = Menu::house();
+
+        let no_toast = OrderItem::new("Breakfast", 1);
+        assert!(matches!(
+            menu.unit_price(&no_toast),
+            Err(MenuError::MissingChoice { .. })
+        ));
+        let bagel = OrderItem::new("Breakfast", 1).with_choice("toast", "Bagel");
+        assert!(matches!(
+            menu.unit_price(&bagel),
+            Err(MenuError::UnknownOption { .. })
+        ));
+        let cake = OrderItem::new("Cake", 1);
+        assert!(matches!(
+            menu.unit_price(&cake),
+            Err(MenuError::UnknownItem(_))
+        ));
+    }
+
+    #[test]
+    fn loads_json_and_validates() {
+        let json = r#"{ "items": [
+            { "name": "Tea", "category": "drink", "price_cents": 250,
+              "modifiers": [{ "name": "milk", "options": [{ "name": "Oat", "price_cents": 40 }] }] }
+        ] }"#;
+        let menu = Menu::from_json(json).unwrap();
+        let tea = OrderItem::new("Tea", 1).with_choice("milk", "Oat");
+        assert_eq!(menu.unit_price(&tea).unwrap(), 290);
+
+        let twice = r#"{ "items": [
+            { "name": "Tea", "category": "drink", "price_cents": 250 },
+            { "name": "Tea", "category": "drink", "price_cents": 300 }
+        ] }"#;
+        assert!(matches!(
+            Menu::from_json(twice),
+            Err(MenuError::DuplicateItem(_))
+        ));
+        assert!(matches!(
+            Menu::from_toml("items = 3"),
+            Err(MenuError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+// Runs the restaurant simulation from the command line, e.g.
+// cargo run --bin simulation -- --seed 42 --rate 20 --tables 2,2,4,4,6
+use std::str::FromStr;
+use std::{env, process};
+
+use packages_crates_modules::menu::Menu;
+use packages_crates_modules::simulation::{self, SimulationConfig};
+
+const USAGE: &str =
+    "usage: simulation [--seed N] [--hours N] [--rate PARTIES_PER_HOUR] [--tables 2,4,...]
+                  [--max-party N] [--patience MINUTES] [--eating MINUTES] [--menu PATH]";
+
+fn main() {
+    let (config, menu) = match parse_args(env::args().skip(1)) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    println!("{}", simulation::run(&config, &menu));
+}
+
+fn parse_args<I>(args: I) -> Result<(SimulationConfig, Menu), String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut config = SimulationConfig::default();
+    let mut menu = Menu::house();
+
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+
+        match flag.as_str() {
+            "--seed" => config.seed = parse(&flag, &value()?)?,
+            "--hours" => config.hours = parse(&flag, &value()?)?,
+            "--rate" => config.arrivals_per_hour = parse(&flag, &value()?)?,
+            "--tables" => {
+                config.tables = value()?
+                    .split(',')
+                    .map(|capacity| parse(&flag, capacity.trim()))
+                    .collect::<Result<_, _>>()?;
+            }
+            "--max-party" => config.max_party_size = parse(&flag, &value()?)?,
+            "--patience" => config.patience_minutes = parse(&flag, &value()?)?,
+            "--eating" => config.eating_minutes = parse(&flag, &value()?)?,
+            "--menu" => {
+                let path = value()?;
+                menu = Menu::load(path.as_ref()).map_err(|error| error.to_string())?;
+            }
+            _ => return Err(format!("unknown argument {}", flag)),
+        }
+    }
+
+    if !(1..=simulation::MAX_HOURS).contains(&config.hours) {
+        return Err(format!(
+            "--hours must be between 1 and {}",
+            simulation::MAX_HOURS
+        ));
+    }
+    if config.arrivals_per_hour < 0.0 || !config.arrivals_per_hour.is_finite() {
+        return Err(String::from("--rate must be a positive number"));
+    }
+    Ok((config, menu))
+}
+
+fn parse<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} does not accept {:?}", flag, value))
+}
@@ -0,0 +1,539 @@
+// Orders that leave the restaurant with a driver instead of being carried to a table.
+// A delivery is booked against an order take_order created, dispatched once the kitchen has it ready,
+// and the order counts as served when the driver hands it over.
+//
+//   Waiting -> Dispatched -> EnRoute -> Delivered
+//                  |            |          |
+//                  +---------> Failed      |
+//                  ^             |         |
+//                  +-------------+---------+   (another attempt, or a remade order going out again)
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::back_of_house::kitchen::Minutes;
+use crate::front_of_house::hosting::TableId;
+use crate::front_of_house::serving::{Order, OrderError, OrderId, OrderStatus};
+
+pub type DriverId = u32;
+
+// The table delivery orders are taken for. TableInventory numbers real tables from 1, so it never clashes.
+pub const DELIVERY_TABLE: TableId = 0;
+
+// Parking and walking up to the door, on top of the drive itself.
+pub const HANDOVER_MINUTES: Minutes = 3;
+
+// Metres east and north of the restaurant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Location {
+    pub east_m: i32,
+    pub north_m: i32,
+}
+
+impl Location {
+    pub const RESTAURANT: Location = Location {
+        east_m: 0,
+        north_m: 0,
+    };
+
+    pub fn new(east_m: i32, north_m: i32) -> Location {
+        Location { east_m, north_m }
+    }
+
+    // Streets run on a grid, so a driver covers the distance along both directions instead of a straight line.
+    // A u64 because across the whole i32 grid the two directions together do not fit in a u32.
+    pub fn distance_m(self, other: Location) -> u64 {
+        u64::from(self.east_m.abs_diff(other.east_m))
+            + u64::from(self.north_m.abs_diff(other.north_m))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    pub street: String,
+    pub location: Location,
+}
+
+impl Address {
+    pub fn new(street: &str, location: Location) -> Address {
+        Address {
+            street: String::from(street),
+            location,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeliveryStatus {
+    Waiting,
+    Dispatched,
+    EnRoute,
+    Delivered,
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn can_move_to(self, next: DeliveryStatus) -> bool {
+        use DeliveryStatus::*;
+
+        matches!(
+            (self, next),
+            (Waiting, Dispatched)
+                | (Dispatched, EnRoute)
+                | (Dispatched, Failed)
+                | (EnRoute, Delivered)
+                | (EnRoute, Failed)
+                | (Failed, Dispatched)
+                | (Delivered, Dispatched)
+        )
+    }
+
+    // A driver is out with the order.
+    pub fn is_out(self) -> bool {
+        matches!(self, DeliveryStatus::Dispatched | DeliveryStatus::EnRoute)
+    }
+}
+
+impl fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            DeliveryStatus::Waiting => "waiting",
+            DeliveryStatus::Dispatched => "dispatched",
+            DeliveryStatus::EnRoute => "en-route",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryError {
+    Order(OrderError),
+    EmptyAddress,
+    AlreadyBooked(OrderId),
+    NotBooked(OrderId),
+    NotReady {
+        order: OrderId,
+        status: OrderStatus,
+    },
+    // Every driver is out. `next_free` is when the first one is expected back, if there are drivers at all.
+    NoDriverAvailable {
+        next_free: Option<Minutes>,
+    },
+    IllegalTransition {
+        order: OrderId,
+        from: DeliveryStatus,
+        to: DeliveryStatus,
+    },
+}
+
+impl fmt::Display for DeliveryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeliveryError::Order(error) => write!(f, "{}", error),
+            DeliveryError::EmptyAddress => write!(f, "a delivery needs a street address"),
+            DeliveryError::AlreadyBooked(order) => {
+                write!(f, "order {} is already booked for delivery", order)
+            }
+            DeliveryError::NotBooked(order) => {
+                write!(f, "order {} is not booked for delivery", order)
+            }
+            DeliveryError::NotReady { order, status } => {
+                write!(f, "order {} is {}, not ready to go out", order, status)
+            }
+            DeliveryError::NoDriverAvailable {
+                next_free: Some(minute),
+            } => write!(
+                f,
+                "every driver is out, the first is back at minute {}",
+                minute
+            ),
+            DeliveryError::NoDriverAvailable { next_free: None } => {
+                write!(f, "there are no drivers")
+            }
+            DeliveryError::IllegalTransition { order, from, to } => write!(
+                f,
+                "delivery of order {} cannot go from {} to {}",
+                order, from, to
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DeliveryError {}
+
+impl From<OrderError> for DeliveryError {
+    fn from(error: OrderError) -> DeliveryError {
+        DeliveryError::Order(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Driver {
+    pub id: DriverId,
+    pub name: String,
+    pub speed_kmh: u32,
+    // When the driver is back at the restaurant and can take the next order.
+    pub available_at: Minutes,
+    pub delivering: Option<OrderId>,
+}
+
+impl Driver {
+    pub fn is_free(&self, now: Minutes) -> bool {
+        self.delivering.is_none() && self.available_at <= now
+    }
+
+    // Minutes to drive `distance_m` metres, rounded up. A drive too long to count in Minutes takes Minutes::MAX.
+    pub fn travel_minutes(&self, distance_m: u64) -> Minutes {
+        let metres_per_hour = u64::from(self.speed_kmh.max(1)) * 1000;
+        let minutes = distance_m.saturating_mul(60).div_ceil(metres_per_hour);
+        Minutes::try_from(minutes).unwrap_or(Minutes::MAX)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery {
+    pub order: OrderId,
+    pub address: Address,
+    pub status: DeliveryStatus,
+    pub driver: Option<DriverId>,
+    // When the current attempt is expected at the door.
+    pub eta: Option<Minutes>,
+    pub attempts: u32,
+    // Every status change with the minute it happened, oldest first.
+    pub history: Vec<(Minutes, DeliveryStatus)>,
+}
+
+// The drivers and every delivery they have been sent on.
+#[derive(Debug, Default)]
+pub struct Dispatch {
+    drivers: Vec<Driver>,
+    deliveries: BTreeMap<OrderId, Delivery>,
+}
+
+impl Dispatch {
+    pub fn new() -> Dispatch {
+        Dispatch::default()
+    }
+
+    pub fn add_driver(&mut self, name: &str, speed_kmh: u32) -> DriverId {
+        let id = self.drivers.len() as DriverId + 1;
+        self.drivers.push(Driver {
+            id,
+            name: String::from(name),
+            speed_kmh,
+            available_at: 0,
+            delivering: None,
+        });
+        id
+    }
+
+    pub fn drivers(&self) -> &[Driver] {
+        &self.drivers
+    }
+
+    pub fn get(&self, order: OrderId) -> Option<&Delivery> {
+        self.deliveries.get(&order)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Delivery> {
+        self.deliveries.values()
+    }
+
+    pub fn with_status(&self, status: DeliveryStatus) -> impl Iterator<Item = &Delivery> {
+        self.deliveries
+            .values()
+            .filter(move |delivery| delivery.status == status)
+    }
+
+    // Marks `order` to go out to `address` once it is cooked.
+    pub fn book(&mut self, order: &Order, address: Address) -> Result<(), DeliveryError> {
+        if address.street.trim().is_empty() {
+            return Err(DeliveryError::EmptyAddress);
+        }
+        if self.deliveries.contains_key(&order.id()) {
+            return Err(DeliveryError::AlreadyBooked(order.id()));
+        }
+
+        self.deliveries.insert(
+            order.id(),
+            Delivery {
+                order: order.id(),
+                address,
+                status: DeliveryStatus::Waiting,
+                driver: None,
+                eta: None,
+                attempts: 0,
+                history: Vec::new(),
+            },
+        );
+        Ok(())
+    }
+
+    // Gives a ready order to the fastest free driver and works out when it will arrive.
+    pub fn dispatch(&mut self, order: &Order, now: Minutes) -> Result<&Delivery, DeliveryError> {
+        if order.status() != OrderStatus::Ready {
+            return Err(DeliveryError::NotReady {
+                order: order.id(),
+                status: order.status(),
+            });
+        }
+        let delivery = self
+            .deliveries
+            .get(&order.id())
+            .ok_or(DeliveryError::NotBooked(order.id()))?;
+        check_transition(delivery, DeliveryStatus::Dispatched)?;
+
+        let Some(index) = self
+            .drivers
+            .iter()
+            .enumerate()
+            .filter(|(_, driver)| driver.is_free(now))
+            .min_by_key(|(_, driver)| (Reverse(driver.speed_kmh), driver.id))
+            .map(|(index, _)| index)
+        else {
+            let next_free = self
+                .drivers
+                .iter()
+                .map(|driver| self.expected_back(driver).max(now))
+                .min();
+            return Err(DeliveryError::NoDriverAvailable { next_free });
+        };
+
+        let delivery = self.deliveries.get_mut(&order.id()).expect("checked above");
+        let driver = &mut self.drivers[index];
+        let distance = Location::RESTAURANT.distance_m(delivery.address.location);
+        driver.delivering = Some(order.id());
+        delivery.driver = Some(driver.id);
+        delivery.eta = Some(
+            now.saturating_add(driver.travel_minutes(distance))
+                .saturating_add(HANDOVER_MINUTES),
+        );
+        delivery.attempts += 1;
+        set_status(delivery, DeliveryStatus::Dispatched, now);
+        Ok(delivery)
+    }
+
+    // The driver has left with the order.
+    pub fn en_route(&mut self, order: OrderId, now: Minutes) -> Result<(), DeliveryError> {
+        let delivery = self.delivery_mut(order)?;
+        check_transition(delivery, DeliveryStatus::EnRoute)?;
+        set_status(delivery, DeliveryStatus::EnRoute, now);
+        Ok(())
+    }
+
+    // The guest has the food, so the order is served. The driver heads back to the restaurant.
+    pub fn delivered(&mut self, order: &mut Order, now: Minutes) -> Result<(), DeliveryError> {
+        let delivery = self.delivery_mut(order.id())?;
+        check_transition(delivery, DeliveryStatus::Delivered)?;
+        order.advance(OrderStatus::Served)?;
+        set_status(delivery, DeliveryStatus::Delivered, now);
+        self.release_driver(order.id(), now);
+        Ok(())
+    }
+
+    // Nobody answered the door, or the address was wrong. The order can be dispatched again.
+    pub fn failed(&mut self, order: OrderId, now: Minutes) -> Result<(), DeliveryError> {
+        let delivery = self.delivery_mut(order)?;
+        check_transition(delivery, DeliveryStatus::Failed)?;
+        set_status(delivery, DeliveryStatus::Failed, now);
+        self.release_driver(order, now);
+        Ok(())
+    }
+
+    // Minutes until the order is expected at the door, None unless a driver is out with it.
+    pub fn eta(&self, order: OrderId, now: Minutes) -> Option<Minutes> {
+        let delivery = self.deliveries.get(&order)?;
+        if !delivery.status.is_out() {
+            return None;
+        }
+        delivery.eta.map(|eta| eta.saturating_sub(now))
+    }
+
+    fn delivery_mut(&mut self, order: OrderId) -> Result<&mut Delivery, DeliveryError> {
+        self.deliveries
+            .get_mut(&order)
+            .ok_or(DeliveryError::NotBooked(order))
+    }
+
+    // When `driver` should be back at the restaurant: after the drive back from their current delivery if they are out.
+    fn expected_back(&self, driver: &Driver) -> Minutes {
+        let Some(delivery) = driver
+            .delivering
+            .and_then(|order| self.deliveries.get(&order))
+        else {
+            return driver.available_at;
+        };
+        let distance = Location::RESTAURANT.distance_m(delivery.address.location);
+        delivery
+            .eta
+            .unwrap_or(driver.available_at)
+            .saturating_add(driver.travel_minutes(distance))
+    }
+
+    // Frees the driver of `order` once they have driven back from where the order was.
+    fn release_driver(&mut self, order: OrderId, now: Minutes) {
+        let delivery = &self.deliveries[&order];
+        let distance = Location::RESTAURANT.distance_m(delivery.address.location);
+        if let Some(driver) = self
+            .drivers
+            .iter_mut()
+            .find(|driver| Some(driver.id) == delivery.driver)
+        {
+            driver.delivering = None;
+            driver.available_at = now.saturating_add(driver.travel_minutes(distance));
+        }
+    }
+}
+
+fn check_transition(delivery: &Delivery, next: DeliveryStatus) -> Result<(), DeliveryError> {
+    if delivery.status.can_move_to(next) {
+        Ok(())
+    } else {
+        Err(DeliveryError::IllegalTransition {
+            order: delivery.order,
+            from: delivery.status,
+            to: next,
+        })
+    }
+}
+
+fn set_status(delivery: &mut Delivery, status: DeliveryStatus, now: Minutes) {
+    delivery.status = status;
+    delivery.history.push((now, status));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::front_of_house::serving::{take_order, OrderBook, OrderItem};
+
+    // A delivery order that has been cooked.
+    fn ready_order(book: &mut OrderBook) -> OrderId {
+        let id = take_order(book, DELIVERY_TABLE, vec![OrderItem::new("Soup", 1)]).unwrap();
+        let order = book.get_mut(id).unwrap();
+        order.advance(OrderStatus::Cooking).unwrap();
+        order.advance(OrderStatus::Ready).unwrap();
+        id
+    }
+
+    #[test]
+    fn distance_follows_the_street_grid() {
+        assert_eq!(
+            Location::new(-300, 400).distance_m(Location::RESTAURANT),
+            700
+        );
+
+        let mut dispatch = Dispatch::new();
+        dispatch.add_driver("Bike", 15);
+        // 2 km at 15 km/h is 8 minutes.
+        assert_eq!(dispatch.drivers()[0].travel_minutes(2000), 8);
+        assert_eq!(dispatch.drivers()[0].travel_minutes(2001), 9);
+
+        // Opposite corners of the grid, driven at the fastest speed there is, neither overflow.
+        let far = Location::new(i32::MIN, i32::MIN).distance_m(Location::new(i32::MAX, i32::MAX));
+        assert_eq!(far, 2 * u64::from(u32::MAX));
+        dispatch.add_driver("Rocket", u32::MAX);
+        assert_eq!(dispatch.drivers()[1].travel_minutes(far), 1);
+        assert_eq!(dispatch.drivers()[0].travel_minutes(u64::MAX), Minutes::MAX);
+    }
+
+    #[test]
+    fn delivers_with_the_fastest_free_driver() {
+        let mut orders = OrderBook::new();
+        let id = ready_order(&mut orders);
+        let mut dispatch = Dispatch::new();
+        dispatch.add_driver("Bike", 15);
+        let car = dispatch.add_driver("Car", 30);
+        let address = Address::new("1 Crab Lane", Location::new(1500, 1000));
+        dispatch.book(orders.get(id).unwrap(), address).unwrap();
+
+        let delivery = dispatch.dispatch(orders.get(id).unwrap(), 10).unwrap();
+        assert_eq!(delivery.driver, Some(car));
+        // 2.5 km at 30 km/h is 5 minutes, plus the handover.
+        assert_eq!(delivery.eta, Some(18));
+        assert_eq!(dispatch.eta(id, 12), Some(6));
+
+        dispatch.en_route(id, 11).unwrap();
+        dispatch.delivered(orders.get_mut(id).unwrap(), 17).unwrap();
+        assert_eq!(orders.get(id).unwrap().status(), OrderStatus::Served);
+        assert_eq!(dispatch.eta(id, 17), None);
+        assert_eq!(dispatch.drivers()[1].available_at, 22);
+        let statuses: Vec<_> = dispatch
+            .get(id)
+            .unwrap()
+            .history
+            .iter()
+            .map(|&(_, s)| s)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                DeliveryStatus::Dispatched,
+                DeliveryStatus::EnRoute,
+                DeliveryStatus::Delivered
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_deliveries_can_go_out_again() {
+        let mut orders = OrderBook::new();
+        let id = ready_order(&mut orders);
+        let mut dispatch = Dispatch::new();
+        dispatch.add_driver("Bike", 12);
+        dispatch
+            .book(
+                orders.get(id).unwrap(),
+                Address::new("Nowhere 9", Location::new(0, 1000)),
+            )
+            .unwrap();
+
+        dispatch.dispatch(orders.get(id).unwrap(), 0).unwrap();
+        dispatch.failed(id, 9).unwrap();
+        assert_eq!(orders.get(id).unwrap().status(), OrderStatus::Ready);
+        assert_eq!(
+            dispatch.dispatch(orders.get(id).unwrap(), 10).unwrap_err(),
+            DeliveryError::NoDriverAvailable {
+                next_free: Some(14)
+            }
+        );
+
+        let retry = dispatch.dispatch(orders.get(id).unwrap(), 14).unwrap();
+        assert_eq!(retry.attempts, 2);
+    }
+
+    #[test]
+    fn rejects_orders_that_cannot_go_out() {
+        let mut orders = OrderBook::new();
+        let id = take_order(&mut orders, DELIVERY_TABLE, vec![OrderItem::new("Pie", 1)]).unwrap();
+        let mut dispatch = Dispatch::new();
+        dispatch.add_driver("Bike", 15);
+        let order = orders.get(id).unwrap();
+
+        assert_eq!(
+            dispatch.book(order, Address::new("  ", Location::RESTAURANT)),
+            Err(DeliveryError::EmptyAddress)
+        );
+        assert_eq!(
+            dispatch.dispatch(order, 0).unwrap_err(),
+            DeliveryError::NotReady {
+                order: id,
+                status: OrderStatus::Placed
+            }
+        );
+        dispatch
+            .book(order, Address::new("2 Crab Lane", Location::new(10, 10)))
+            .unwrap();
+        assert_eq!(
+            dispatch.book(order, Address::new("2 Crab Lane", Location::new(10, 10))),
+            Err(DeliveryError::AlreadyBooked(id))
+        );
+        assert!(matches!(
+            dispatch.en_route(id, 0),
+            Err(DeliveryError::IllegalTransition { .. })
+        ));
+    }
+}
@@ -0,0 +1,422 @@
+use std::fmt;
+
+use crate::back_of_house::menu::{Cents, Menu, MenuError};
+use crate::front_of_house::serving::{Order, OrderError, OrderId};
+
+// Rates are given in basis points, hundredths of a percent, so 8.25% is 825.
+pub type BasisPoints = u32;
+
+pub const DEFAULT_TIP_RATES: [BasisPoints; 3] = [1500, 1800, 2000];
+
+// `amount` times `rate`, rounded to the nearest cent with halves rounded up.
+pub fn percent_of(amount: Cents, rate: BasisPoints) -> Cents {
+    ((u128::from(amount) * u128::from(rate) + 5_000) / 10_000) as Cents
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Discount {
+    Percent(BasisPoints),
+    Fixed(Cents),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coupon {
+    pub code: String,
+    pub discount: Discount,
+    // The subtotal the order has to reach before the coupon can be used.
+    pub minimum_cents: Cents,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BillSettings {
+    pub tax_rate: BasisPoints,
+    pub coupons: Vec<Coupon>,
+    pub tip_cents: Cents,
+}
+
+impl BillSettings {
+    pub fn with_tax(tax_rate: BasisPoints) -> BillSettings {
+        BillSettings {
+            tax_rate,
+            coupons: Vec::new(),
+            tip_cents: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BillingError {
+    Order(OrderError),
+    Menu(MenuError),
+    CouponBelowMinimum { code: String, minimum_cents: Cents },
+    DuplicateCoupon(String),
+    NoDiners,
+    UnassignedLine(usize),
+    UnknownDiner { line: usize, diner: usize },
+}
+
+impl fmt::Display for BillingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BillingError::Order(error) => write!(f, "{}", error),
+            BillingError::Menu(error) => write!(f, "{}", error),
+            BillingError::CouponBelowMinimum {
+                code,
+                minimum_cents,
+            } => write!(
+                f,
+                "coupon {} needs a subtotal of at least {}",
+                code,
+                format_cents(*minimum_cents)
+            ),
+            BillingError::DuplicateCoupon(code) => write!(f, "coupon {} was used twice", code),
+            BillingError::NoDiners => {
+                write!(f, "a bill has to be split between at least one diner")
+            }
+            BillingError::UnassignedLine(line) => write!(f, "nobody is paying for line {}", line),
+            BillingError::UnknownDiner { line, diner } => {
+                write!(
+                    f,
+                    "line {} is assigned to diner {} who is not at the table",
+                    line, diner
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BillingError {}
+
+impl From<OrderError> for BillingError {
+    fn from(error: OrderError) -> BillingError {
+        BillingError::Order(error)
+    }
+}
+
+impl From<MenuError> for BillingError {
+    fn from(error: MenuError) -> BillingError {
+        BillingError::Menu(error)
+    }
+}
+
+// "$12.05" for 1205 cents.
+pub fn format_cents(cents: Cents) -> String {
+    format!("${}.{:02}", cents / 100, cents % 100)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BillLine {
+    pub name: String,
+    pub choices: Vec<String>,
+    pub quantity: u32,
+    pub unit_cents: Cents,
+    pub total_cents: Cents,
+}
+
+// An itemised bill. Every amount is in whole cents, and the parts always add up to the total exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bill {
+    order: OrderId,
+    lines: Vec<BillLine>,
+    subtotal: Cents,
+    discount: Cents,
+    tax_rate: BasisPoints,
+    tax: Cents,
+    tip: Cents,
+}
+
+impl Bill {
+    // Discounts come off the subtotal before tax. They are applied in order and can never take the bill below zero.
+    pub fn for_order(
+        order: &Order,
+        menu: &Menu,
+        settings: &BillSettings,
+    ) -> Result<Bill, BillingError> {
+        let mut lines = Vec::new();
+        for item in order.items() {
+            let unit_cents = menu.unit_price(item)?;
+            lines.push(BillLine {
+                name: item.name.clone(),
+                choices: item
+                    .choices
+                    .iter()
+                    .map(|(_, option)| option.clone())
+                    .collect(),
+                quantity: item.quantity,
+                unit_cents,
+                total_cents: unit_cents * Cents::from(item.quantity),
+            });
+        }
+        let subtotal: Cents = lines.iter().map(|line| line.total_cents).sum();
+
+        let mut discount: Cents = 0;
+        for (index, coupon) in settings.coupons.iter().enumerate() {
+            if settings.coupons[..index]
+                .iter()
+                .any(|used| used.code == coupon.code)
+            {
+                return Err(BillingError::DuplicateCoupon(coupon.code.clone()));
+            }
+            if subtotal < coupon.minimum_cents {
+                return Err(BillingError::CouponBelowMinimum {
+                    code: coupon.code.clone(),
+                    minimum_cents: coupon.minimum_cents,
+                });
+            }
+            let remaining = subtotal - discount;
+            let amount = match coupon.discount {
+                Discount::Percent(rate) => percent_of(remaining, rate),
+                Discount::Fixed(cents) => cents,
+            };
+            discount += amount.min(remaining);
+        }
+
+        Ok(Bill {
+            order: order.id(),
+            lines,
+            subtotal,
+            discount,
+            tax_rate: settings.tax_rate,
+            tax: percent_of(subtotal - discount, settings.tax_rate),
+            tip: settings.tip_cents,
+        })
+    }
+
+    pub fn order(&self) -> OrderId {
+        self.order
+    }
+
+    pub fn lines(&self) -> &[BillLine] {
+        &self.lines
+    }
+
+    pub fn subtotal(&self) -> Cents {
+        self.subtotal
+    }
+
+    pub fn discount(&self) -> Cents {
+        self.discount
+    }
+
+    pub fn tax_rate(&self) -> BasisPoints {
+        self.tax_rate
+    }
+
+    pub fn tax(&self) -> Cents {
+        self.tax
+    }
+
+    pub fn tip(&self) -> Cents {
+        self.tip
+    }
+
+    pub fn total(&self) -> Cents {
+        self.subtotal - self.discount + self.tax + self.tip
+    }
+
+    // Tips are suggested on the subtotal before discounts, the staff did the same work either way.
+    pub fn tip_suggestions(&self, rates: &[BasisPoints]) -> Vec<(BasisPoints, Cents)> {
+        rates
+            .iter()
+            .map(|&rate| (rate, percent_of(self.subtotal, rate)))
+            .collect()
+    }
+
+    // Each diner pays the same, give or take a cent. The first diners pick up the leftover cents.
+    pub fn split_evenly(&self, diners: usize) -> Result<Vec<Cents>, BillingError> {
+        if diners == 0 {
+            return Err(BillingError::NoDiners);
+        }
+        Ok(allocate(self.total(), &vec![1; diners]))
+    }
+
+    // `owners[line]` lists the diners sharing that line of the bill. Each diner pays for their share of the items,
+    // and the discount, tax and tip are spread in proportion to what they ordered.
+    pub fn split_by_item(
+        &self,
+        owners: &[Vec<usize>],
+        diners: usize,
+    ) -> Result<Vec<Cents>, BillingError> {
+        if diners == 0 {
+            return Err(BillingError::NoDiners);
+        }
+
+        let mut ordered: Vec<Cents> = vec![0; diners];
+        for (line, bill_line) in self.lines.iter().enumerate() {
+            let sharing = owners.get(line).filter(|sharing| !sharing.is_empty());
+            let sharing = sharing.ok_or(BillingError::UnassignedLine(line))?;
+            if let Some(&diner) = sharing.iter().find(|&&diner| diner >= diners) {
+                return Err(BillingError::UnknownDiner { line, diner });
+            }
+
+            let shares = allocate(bill_line.total_cents, &vec![1; sharing.len()]);
+            for (&diner, share) in sharing.iter().zip(shares) {
+                ordered[diner] += share;
+            }
+        }
+
+        Ok(allocate(self.total(), &ordered))
+    }
+}
+
+// Splits `total` in proportion to `weights` so that the parts add up to `total` exactly.
+// Each part is rounded down first and the cents left over go to the largest remainders, earlier parts winning ties.
+// If every weight is zero the total is split evenly. With no weights there is nobody to give it to.
+fn allocate(total: Cents, weights: &[Cents]) -> Vec<Cents> {
+    if weights.is_empty() {
+        return Vec::new();
+    }
+    let weight_sum: u128 = weights.iter().map(|&w| u128::from(w)).sum();
+    if weight_sum == 0 {
+        return allocate(total, &vec![1; weights.len()]);
+    }
+
+    let mut parts = Vec::with_capacity(weights.len());
+    let mut remainders = Vec::with_capacity(weights.len());
+    for (index, &weight) in weights.iter().enumerate() {
+        let exact = u128::from(total) * u128::from(weight);
+        parts.push((exact / weight_sum) as Cents);
+        remainders.push((exact % weight_sum, index));
+    }
+
+    let left_over = total - parts.iter().sum::<Cents>();
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for &(_, index) in remainders.iter().take(left_over as usize) {
+        parts[index] += 1;
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::front_of_house::serving::{take_order, OrderBook, OrderItem};
+
+    fn order(items: Vec<OrderItem>) -> Order {
+        let mut book = OrderBook::new();
+        let id = take_order(&mut book, 1, items).unwrap();
+        book.get(id).unwrap().clone()
+    }
+
+    fn coupon(code: &str, discount: Discount, minimum_cents: Cents) -> Coupon {
+        Coupon {
+            code: String::from(code),
+            discount,
+            minimum_cents,
+        }
+    }
+
+    #[test]
+    fn itemises_and_taxes_the_order() {
+        let order = order(vec![
+            OrderItem::new("Breakfast", 2).with_choice("toast", "Sourdough"),
+            OrderItem::new("Coffee", 3),
+        ]);
+        let bill = Bill::for_order(&order, &Menu::house(), &BillSettings::with_tax(825)).unwrap();
+
+        assert_eq!(bill.lines().len(), 2);
+        assert_eq!(bill.lines()[0].unit_cents, 1300);
+        assert_eq!(bill.subtotal(), 3500);
+        // 8.25% of 3500 is 288.75, rounded to 289.
+        assert_eq!(bill.tax(), 289);
+        assert_eq!(bill.total(), 3789);
+        assert_eq!(
+            bill.tip_suggestions(&DEFAULT_TIP_RATES),
+            vec![(1500, 525), (1800, 630), (2000, 700)]
+        );
+    }
+
+    #[test]
+    fn applies_coupons_before_tax() {
+        let order = order(vec![OrderItem::new("Soup", 2)]);
+        let settings = BillSettings {
+            tax_rate: 1000,
+            coupons: vec![
+                coupon("TENOFF", Discount::Percent(1000), 0),
+                coupon("TWO", Discount::Fixed(200), 1000),
+            ],
+            tip_cents: 150,
+        };
+        let bill = Bill::for_order(&order, &Menu::house(), &settings).unwrap();
+
+        // 1300 - 130 - 200 = 970, plus 97 tax and the 150 tip.
+        assert_eq!(bill.discount(), 330);
+        assert_eq!(bill.tax(), 97);
+        assert_eq!(bill.total(), 1217);
+    }
+
+    #[test]
+    fn coupons_have_limits() {
+        let menu = Menu::house();
+        let small = order(vec![OrderItem::new("Coffee", 1)]);
+
+        let mut settings = BillSettings::with_tax(0);
+        settings.coupons = vec![coupon("BIG", Discount::Fixed(500), 2000)];
+        assert!(matches!(
+            Bill::for_order(&small, &menu, &settings),
+            Err(BillingError::CouponBelowMinimum { .. })
+        ));
+
+        settings.coupons = vec![coupon("ALL", Discount::Fixed(10_000), 0)];
+        assert_eq!(
+            Bill::for_order(&small, &menu, &settings).unwrap().total(),
+            0
+        );
+
+        settings.coupons = vec![
+            coupon("A", Discount::Fixed(1), 0),
+            coupon("A", Discount::Fixed(1), 0),
+        ];
+        assert!(matches!(
+            Bill::for_order(&small, &menu, &settings),
+            Err(BillingError::DuplicateCoupon(_))
+        ));
+    }
+
+    #[test]
+    fn even_split_adds_up() {
+        let order = order(vec![OrderItem::new("Coffee", 1), OrderItem::new("Pie", 1)]);
+        let bill = Bill::for_order(&order, &Menu::house(), &BillSettings::with_tax(0)).unwrap();
+
+        assert_eq!(bill.split_evenly(3).unwrap(), vec![299, 298, 298]);
+        assert!(matches!(bill.split_evenly(0), Err(BillingError::NoDiners)));
+    }
+
+    #[test]
+    fn split_by_item_spreads_tax_and_tip() {
+        let order = order(vec![
+            OrderItem::new("Burger", 1).with_choice("side", "Fries"),
+            OrderItem::new("Coffee", 1),
+            OrderItem::new("Pie", 1),
+        ]);
+        let mut settings = BillSettings::with_tax(825);
+        settings.tip_cents = 500;
+        let bill = Bill::for_order(&order, &Menu::house(), &settings).unwrap();
+
+        // Diner 0 had the burger, diner 1 the coffee, and they shared the pie.
+        let shares = bill
+            .split_by_item(&[vec![0], vec![1], vec![0, 1]], 2)
+            .unwrap();
+        assert_eq!(shares.iter().sum::<Cents>(), bill.total());
+        assert!(shares[0] > shares[1]);
+
+        assert!(matches!(
+            bill.split_by_item(&[vec![0], vec![], vec![1]], 2),
+            Err(BillingError::UnassignedLine(1))
+        ));
+        assert!(matches!(
+            bill.split_by_item(&[vec![0], vec![2], vec![1]], 2),
+            Err(BillingError::UnknownDiner { line: 1, diner: 2 })
+        ));
+    }
+
+    #[test]
+    fn allocation_never_loses_a_cent() {
+        for total in [0, 1, 99, 100, 12_345] {
+            for weights in [vec![1, 1, 1], vec![3, 0, 7], vec![0, 0], vec![1]] {
+                assert_eq!(allocate(total, &weights).iter().sum::<Cents>(), total);
+            }
+        }
+        assert_eq!(allocate(100, &[]), Vec::<Cents>::new());
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+pub type PartyId = u32;
+pub type TableId = u32;
+
+// How long a party is expected to hold a table, used for wait estimates when no other value is given.
+pub const DEFAULT_TURN_MINUTES: u32 = 45;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostingError {
+    EmptyParty,
+    UnknownParty(PartyId),
+    UnknownTable(TableId),
+    TableOccupied(TableId),
+    TableTooSmall {
+        table: TableId,
+        capacity: u32,
+        party_size: u32,
+    },
+}
+
+impl fmt::Display for HostingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HostingError::EmptyParty => write!(f, "a party needs at least one guest"),
+            HostingError::UnknownParty(id) => write!(f, "there is no party {}", id),
+            HostingError::UnknownTable(id) => write!(f, "there is no table {}", id),
+            HostingError::TableOccupied(id) => write!(f, "table {} is already taken", id),
+            HostingError::TableTooSmall {
+                table,
+                capacity,
+                party_size,
+            } => write!(
+                f,
+                "table {} seats {}, too small for a party of {}",
+                table, capacity, party_size
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HostingError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Party {
+    pub id: PartyId,
+    pub name: String,
+    pub size: u32,
+}
+
+// Parties waiting for a table, first come first served.
+#[derive(Debug)]
+pub struct Waitlist {
+    parties: VecDeque<Party>,
+    next_id: PartyId,
+    turn_minutes: u32,
+}
+
+impl Waitlist {
+    pub fn new() -> Waitlist {
+        Waitlist::with_turn_time(DEFAULT_TURN_MINUTES)
+    }
+
+    // `turn_minutes` is how long a seated party is expected to keep its table.
+    pub fn with_turn_time(turn_minutes: u32) -> Waitlist {
+        Waitlist {
+            parties: VecDeque::new(),
+            next_id: 1,
+            turn_minutes,
+        }
+    }
+
+    pub fn add(&mut self, name: &str, size: u32) -> Result<PartyId, HostingError> {
+        let party = self.register(name, size)?;
+        let id = party.id;
+        self.parties.push_back(party);
+        Ok(id)
+    }
+
+    // Gives a party an id without putting it in line, for guests who arrive with a reservation.
+    // Ids come from the same counter as the waitlist, so they never clash at a table.
+    pub fn register(&mut self, name: &str, size: u32) -> Result<Party, HostingError> {
+        if size == 0 {
+            return Err(HostingError::EmptyParty);
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        Ok(Party {
+            id,
+            name: String::from(name),
+            size,
+        })
+    }
+
+    pub fn remove(&mut self, id: PartyId) -> Option<Party> {
+        let index = self.position(id)?;
+        self.parties.remove(index)
+    }
+
+    // How many parties are ahead of `id`, starting at 0 for the party at the front.
+    pub fn position(&self, id: PartyId) -> Option<usize> {
+        self.parties.iter().position(|party| party.id == id)
+    }
+
+    pub fn get(&self, id: PartyId) -> Option<&Party> {
+        self.parties.iter().find(|party| party.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Party> {
+        self.parties.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.parties.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parties.is_empty()
+    }
+
+    // Rough minutes until `id` is seated. Parties ahead take the free tables that fit first,
+    // after that every table big enough frees up once per turn. None if no table is big enough at all.
+    pub fn estimated_wait(&self, id: PartyId, tables: &TableInventory) -> Option<u32> {
+        let ahead = self.position(id)?;
+        let size = self.parties[ahead].size;
+
+        let fitting = tables.iter().filter(|table| table.capacity >= size).count();
+        if fitting == 0 {
+            return None;
+        }
+        let free_fitting = tables.free().filter(|table| table.capacity >= size).count();
+
+        if ahead < free_fitting {
+            return Some(0);
+        }
+        let turns = (ahead - free_fitting) / fitting + 1;
+        Some(turns as u32 * self.turn_minutes)
+    }
+}
+
+impl Default for Waitlist {
+    fn default() -> Waitlist {
+        Waitlist::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub id: TableId,
+    pub capacity: u32,
+    pub occupant: Option<PartyId>,
+}
+
+impl Table {
+    pub fn is_free(&self) -> bool {
+        self.occupant.is_none()
+    }
+}
+
+// Every table in the restaurant and who is sitting at it.
+#[derive(Debug, Default)]
+pub struct TableInventory {
+    tables: Vec<Table>,
+}
+
+impl TableInventory {
+    pub fn new() -> TableInventory {
+        TableInventory::default()
+    }
+
+    pub fn add_table(&mut self, capacity: u32) -> TableId {
+        let id = self.tables.len() as TableId + 1;
+        self.tables.push(Table {
+            id,
+            capacity,
+            occupant: None,
+        });
+        id
+    }
+
+    pub fn get(&self, id: TableId) -> Option<&Table> {
+        self.tables.iter().find(|table| table.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Table> {
+        self.tables.iter()
+    }
+
+    pub fn free(&self) -> impl Iterator<Item = &Table> {
+        self.tables.iter().filter(|table| table.is_free())
+    }
+
+    // The smallest free table that seats `size`, so big tables stay open for big parties.
+    pub fn best_fit(&self, size: u32) -> Option<TableId> {
+        self.best_fit_where(size, |_| true)
+    }
+
+    // best_fit limited to the free tables `allowed` accepts.
+    pub fn best_fit_where<F>(&self, size: u32, allowed: F) -> Option<TableId>
+    where
+        F: Fn(&Table) -> bool,
+    {
+        self.free()
+            .filter(|table| table.capacity >= size && allowed(table))
+            .min_by_key(|table| (table.capacity, table.id))
+            .map(|table| table.id)
+    }
+
+    pub fn occupy(&mut self, id: TableId, party: &Party) -> Result<(), HostingError> {
+        let table = self.get_mut(id)?;
+        if !table.is_free() {
+            return Err(HostingError::TableOccupied(id));
+        }
+        if table.capacity < party.size {
+            return Err(HostingError::TableTooSmall {
+                table: id,
+                capacity: table.capacity,
+                party_size: party.size,
+            });
+        }
+        table.occupant = Some(party.id);
+        Ok(())
+    }
+
+    // Frees the table and returns the party that was sitting there.
+    pub fn release(&mut self, id: TableId) -> Result<Option<PartyId>, HostingError> {
+        Ok(self.get_mut(id)?.occupant.take())
+    }
+
+    fn get_mut(&mut self, id: TableId) -> Result<&mut Table, HostingError> {
+        self.tables
+            .iter_mut()
+            .find(|table| table.id == id)
+            .ok_or(HostingError::UnknownTable(id))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seating {
+    pub party: Party,
+    pub table: TableId,
+}
+
+pub fn add_to_waitlist(
+    waitlist: &mut Waitlist,
+    name: &str,
+    size: u32,
+) -> Result<PartyId, HostingError> {
+    waitlist.add(name, size)
+}
+
+// Seats the first party in line that a free table fits. A big party at the front does not hold up
+// smaller parties behind it while no table is big enough for it.
+pub fn seat_at_table(waitlist: &mut Waitlist, tables: &mut TableInventory) -> Option<Seating> {
+    seat_at_table_avoiding(waitlist, tables, &[])
+}
+
+// Like seat_at_table, but leaves the `held` tables alone, e.g. tables kept for a reservation.
+pub fn seat_at_table_avoiding(
+    waitlist: &mut Waitlist,
+    tables: &mut TableInventory,
+    held: &[TableId],
+) -> Option<Seating> {
+    let (party, table) = waitlist.iter().find_map(|party| {
+        tables
+            .best_fit_where(party.size, |table| !held.contains(&table.id))
+            .map(|table| (party.id, table))
+    })?;
+
+    let party = waitlist.remove(party)?;
+    tables
+        .occupy(table, &party)
+        .expect("best_fit only returns free tables that are big enough");
+    Some(Seating { party, table })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory(capacities: &[u32]) -> TableInventory {
+        let mut tables = TableInventory::new();
+        for &capacity in capacities {
+            tables.add_table(capacity);
+        }
+        tables
+    }
+
+    #[test]
+    fn seats_parties_at_the_best_fitting_table() {
+        let mut tables = inventory(&[6, 2, 4]);
+        let mut waitlist = Waitlist::new();
+        add_to_waitlist(&mut waitlist, "Ferris", 3).unwrap();
+        add_to_waitlist(&mut waitlist, "Corro", 2).unwrap();
+
+        let first = seat_at_table(&mut waitlist, &mut tables).unwrap();
+        assert_eq!((first.party.name.as_str(), first.table), ("Ferris", 3));
+        let second = seat_at_table(&mut waitlist, &mut tables).unwrap();
+        assert_eq!((second.party.name.as_str(), second.table), ("Corro", 2));
+        assert!(waitlist.is_empty());
+    }
+
+    #[test]
+    fn big_party_does_not_block_the_line() {
+        let mut tables = inventory(&[2]);
+        let mut waitlist = Waitlist::new();
+        let big = waitlist.add("Big", 8).unwrap();
+        waitlist.add("Small", 2).unwrap();
+
+        let seated = seat_at_table(&mut waitlist, &mut tables).unwrap();
+        assert_eq!(seated.party.name, "Small");
+        assert_eq!(waitlist.position(big), Some(0));
+        assert_eq!(seat_at_table(&mut waitlist, &mut tables), None);
+    }
+
+    #[test]
+    fn estimates_wait_from_tables_and_queue() {
+        let mut tables = inventory(&[4, 4]);
+        let mut waitlist = Waitlist::with_turn_time(30);
+        let ids: Vec<_> = (0..5).map(|_| waitlist.add("guest", 2).unwrap()).collect();
+        let huge = waitlist.add("Huge", 10).unwrap();
+
+        assert_eq!(waitlist.estimated_wait(ids[1], &tables), Some(0));
+        assert_eq!(waitlist.estimated_wait(ids[2], &tables), Some(30));
+        assert_eq!(waitlist.estimated_wait(ids[4], &tables), Some(60));
+        assert_eq!(waitlist.estimated_wait(huge, &tables), None);
+
+        let first = waitlist.remove(ids[0]).unwrap();
+        tables.occupy(1, &first).unwrap();
+        assert_eq!(waitlist.estimated_wait(ids[1], &tables), Some(0));
+        assert_eq!(waitlist.estimated_wait(ids[2], &tables), Some(30));
+    }
+
+    #[test]
+    fn rejects_invalid_moves() {
+        let mut tables = inventory(&[2]);
+        let mut waitlist = Waitlist::new();
+        assert_eq!(waitlist.add("Nobody", 0), Err(HostingError::EmptyParty));
+
+        let id = waitlist.add("Four", 4).unwrap();
+        let party = waitlist.get(id).unwrap().clone();
+        assert!(matches!(
+            tables.occupy(1, &party),
+            Err(HostingError::TableTooSmall { .. })
+        ));
+        assert_eq!(tables.occupy(9, &party), Err(HostingError::UnknownTable(9)));
+        assert_eq!(tables.release(1), Ok(None));
+    }
+}
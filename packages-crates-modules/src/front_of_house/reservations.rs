@@ -0,0 +1,494 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::hosting::{
+    self, HostingError, Seating, TableId, TableInventory, Waitlist, DEFAULT_TURN_MINUTES,
+};
+
+pub type ReservationId = u32;
+// Days are counted from whatever date the restaurant opened its book on.
+pub type Day = u32;
+
+const MINUTES_PER_DAY: u64 = 24 * 60;
+
+// When a reservation starts: a day and the minutes after midnight on that day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Slot {
+    pub day: Day,
+    pub minute: u32,
+}
+
+impl Slot {
+    // Panics unless `hour` is 0 to 23 and `minute` 0 to 59.
+    pub fn at(day: Day, hour: u32, minute: u32) -> Slot {
+        assert!(hour < 24, "hour {} is not between 0 and 23", hour);
+        assert!(minute < 60, "minute {} is not between 0 and 59", minute);
+        Slot {
+            day,
+            minute: hour * 60 + minute,
+        }
+    }
+
+    // Minutes since midnight on day 0, so stays running past midnight compare with the next day's slots.
+    fn absolute_minute(self) -> u64 {
+        u64::from(self.day) * MINUTES_PER_DAY + u64::from(self.minute)
+    }
+
+    // Whether two stays of `length` minutes starting at these slots share any minute.
+    // Back to back stays, one ending when the other starts, do not overlap.
+    fn overlaps(self, other: Slot, length: u32) -> bool {
+        let (start, other_start) = (self.absolute_minute(), other.absolute_minute());
+        let length = u64::from(length);
+        start < other_start + length && other_start < start + length
+    }
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day {} at {:02}:{:02}",
+            self.day,
+            self.minute / 60,
+            self.minute % 60
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservationStatus {
+    Booked,
+    Seated,
+    Cancelled,
+    NoShow,
+}
+
+impl ReservationStatus {
+    // Booked and seated reservations keep their table for the slot, the others gave it up.
+    pub fn holds_table(self) -> bool {
+        matches!(self, ReservationStatus::Booked | ReservationStatus::Seated)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reservation {
+    pub id: ReservationId,
+    pub name: String,
+    pub size: u32,
+    pub slot: Slot,
+    pub table: TableId,
+    pub status: ReservationStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReservationError {
+    Hosting(HostingError),
+    UnknownReservation(ReservationId),
+    DoubleBooked {
+        table: TableId,
+        existing: ReservationId,
+    },
+    FullyBooked {
+        slot: Slot,
+        size: u32,
+    },
+    NotBooked {
+        reservation: ReservationId,
+        status: ReservationStatus,
+    },
+}
+
+impl fmt::Display for ReservationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReservationError::Hosting(error) => write!(f, "{}", error),
+            ReservationError::UnknownReservation(id) => write!(f, "there is no reservation {}", id),
+            ReservationError::DoubleBooked { table, existing } => write!(
+                f,
+                "table {} is already booked by reservation {}",
+                table, existing
+            ),
+            ReservationError::FullyBooked { slot, size } => {
+                write!(f, "no table for a party of {} is free on {}", size, slot)
+            }
+            ReservationError::NotBooked {
+                reservation,
+                status,
+            } => write!(f, "reservation {} is {:?}, not booked", reservation, status),
+        }
+    }
+}
+
+impl std::error::Error for ReservationError {}
+
+impl From<HostingError> for ReservationError {
+    fn from(error: HostingError) -> ReservationError {
+        ReservationError::Hosting(error)
+    }
+}
+
+// Tables promised to guests ahead of time. The book shares the TableInventory with walk-in seating:
+// it only decides which table a reservation gets, the inventory still says who is sitting where.
+#[derive(Debug)]
+pub struct ReservationBook {
+    reservations: BTreeMap<ReservationId, Reservation>,
+    next_id: ReservationId,
+    turn_minutes: u32,
+}
+
+impl ReservationBook {
+    pub fn new() -> ReservationBook {
+        ReservationBook::with_turn_time(DEFAULT_TURN_MINUTES)
+    }
+
+    // `turn_minutes` is how long a reservation keeps its table from the start of its slot.
+    pub fn with_turn_time(turn_minutes: u32) -> ReservationBook {
+        ReservationBook {
+            reservations: BTreeMap::new(),
+            next_id: 1,
+            turn_minutes,
+        }
+    }
+
+    pub fn get(&self, id: ReservationId) -> Option<&Reservation> {
+        self.reservations.get(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Reservation> {
+        self.reservations.values()
+    }
+
+    // The reservations for `day` in the order the guests are expected.
+    pub fn on_day(&self, day: Day) -> Vec<&Reservation> {
+        let mut reservations: Vec<_> = self
+            .reservations
+            .values()
+            .filter(|reservation| reservation.slot.day == day)
+            .collect();
+        reservations.sort_by_key(|reservation| (reservation.slot, reservation.id));
+        reservations
+    }
+
+    // Books the smallest table that is big enough and not already promised for the slot.
+    pub fn book(
+        &mut self,
+        tables: &TableInventory,
+        name: &str,
+        size: u32,
+        slot: Slot,
+    ) -> Result<ReservationId, ReservationError> {
+        if size == 0 {
+            return Err(HostingError::EmptyParty.into());
+        }
+        let table = tables
+            .iter()
+            .filter(|table| table.capacity >= size && self.conflict(table.id, slot).is_none())
+            .min_by_key(|table| (table.capacity, table.id))
+            .ok_or(ReservationError::FullyBooked { slot, size })?;
+
+        Ok(self.insert(name, size, slot, table.id))
+    }
+
+    // Books a table the guest asked for by number.
+    pub fn book_table(
+        &mut self,
+        tables: &TableInventory,
+        table: TableId,
+        name: &str,
+        size: u32,
+        slot: Slot,
+    ) -> Result<ReservationId, ReservationError> {
+        if size == 0 {
+            return Err(HostingError::EmptyParty.into());
+        }
+        let capacity = tables
+            .get(table)
+            .ok_or(HostingError::UnknownTable(table))?
+            .capacity;
+        if capacity < size {
+            return Err(HostingError::TableTooSmall {
+                table,
+                capacity,
+                party_size: size,
+            }
+            .into());
+        }
+        if let Some(existing) = self.conflict(table, slot) {
+            return Err(ReservationError::DoubleBooked { table, existing });
+        }
+
+        Ok(self.insert(name, size, slot, table))
+    }
+
+    // The reservation that already holds `table` at some point during a stay starting at `slot`.
+    pub fn conflict(&self, table: TableId, slot: Slot) -> Option<ReservationId> {
+        self.reservations
+            .values()
+            .find(|reservation| {
+                reservation.table == table
+                    && reservation.status.holds_table()
+                    && reservation.slot.overlaps(slot, self.turn_minutes)
+            })
+            .map(|reservation| reservation.id)
+    }
+
+    // Tables a walk-in seated at `now` could not finish at before a booked party arrives,
+    // including tables kept for guests who are late but not yet marked as no-shows.
+    pub fn held_tables(&self, now: Slot) -> Vec<TableId> {
+        self.reservations
+            .values()
+            .filter(|reservation| {
+                reservation.status == ReservationStatus::Booked
+                    && reservation.slot.overlaps(now, self.turn_minutes)
+            })
+            .map(|reservation| reservation.table)
+            .collect()
+    }
+
+    // Seats the reserved party at its table. The table has to be empty, a walk-in is never moved.
+    pub fn check_in(
+        &mut self,
+        id: ReservationId,
+        waitlist: &mut Waitlist,
+        tables: &mut TableInventory,
+    ) -> Result<Seating, ReservationError> {
+        let reservation = self.booked_mut(id)?;
+        let party = waitlist.register(&reservation.name, reservation.size)?;
+        tables.occupy(reservation.table, &party)?;
+        reservation.status = ReservationStatus::Seated;
+        Ok(Seating {
+            party,
+            table: reservation.table,
+        })
+    }
+
+    // Cancels a booking. If that frees a table right now, the next party in line that fits gets it.
+    pub fn cancel(
+        &mut self,
+        id: ReservationId,
+        now: Slot,
+        waitlist: &mut Waitlist,
+        tables: &mut TableInventory,
+    ) -> Result<Option<Seating>, ReservationError> {
+        self.booked_mut(id)?.status = ReservationStatus::Cancelled;
+        Ok(self.seat_walk_in(now, waitlist, tables))
+    }
+
+    // The guests never came. Their table goes to the waitlist.
+    pub fn mark_no_show(
+        &mut self,
+        id: ReservationId,
+        now: Slot,
+        waitlist: &mut Waitlist,
+        tables: &mut TableInventory,
+    ) -> Result<Option<Seating>, ReservationError> {
+        self.booked_mut(id)?.status = ReservationStatus::NoShow;
+        Ok(self.seat_walk_in(now, waitlist, tables))
+    }
+
+    // A party left `table`. It goes to the waitlist unless a reservation is due there.
+    pub fn release_table(
+        &self,
+        table: TableId,
+        now: Slot,
+        waitlist: &mut Waitlist,
+        tables: &mut TableInventory,
+    ) -> Result<Option<Seating>, ReservationError> {
+        tables.release(table)?;
+        Ok(self.seat_walk_in(now, waitlist, tables))
+    }
+
+    // Seats the waitlist at `now` without giving away tables that are held for reservations.
+    pub fn seat_walk_in(
+        &self,
+        now: Slot,
+        waitlist: &mut Waitlist,
+        tables: &mut TableInventory,
+    ) -> Option<Seating> {
+        hosting::seat_at_table_avoiding(waitlist, tables, &self.held_tables(now))
+    }
+
+    fn insert(&mut self, name: &str, size: u32, slot: Slot, table: TableId) -> ReservationId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.reservations.insert(
+            id,
+            Reservation {
+                id,
+                name: String::from(name),
+                size,
+                slot,
+                table,
+                status: ReservationStatus::Booked,
+            },
+        );
+        id
+    }
+
+    fn booked_mut(&mut self, id: ReservationId) -> Result<&mut Reservation, ReservationError> {
+        let reservation = self
+            .reservations
+            .get_mut(&id)
+            .ok_or(ReservationError::UnknownReservation(id))?;
+        if reservation.status != ReservationStatus::Booked {
+            return Err(ReservationError::NotBooked {
+                reservation: id,
+                status: reservation.status,
+            });
+        }
+        Ok(reservation)
+    }
+}
+
+impl Default for ReservationBook {
+    fn default() -> ReservationBook {
+        ReservationBook::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory(capacities: &[u32]) -> TableInventory {
+        let mut tables = TableInventory::new();
+        for &capacity in capacities {
+            tables.add_table(capacity);
+        }
+        tables
+    }
+
+    #[test]
+    fn books_best_fit_and_refuses_overbooking() {
+        let tables = inventory(&[4, 2]);
+        let mut book = ReservationBook::with_turn_time(60);
+        let seven = Slot::at(1, 19, 0);
+
+        let pair = book.book(&tables, "Pair", 2, seven).unwrap();
+        assert_eq!(book.get(pair).unwrap().table, 2);
+        let other = book.book(&tables, "Other pair", 2, seven).unwrap();
+        assert_eq!(book.get(other).unwrap().table, 1);
+
+        assert_eq!(
+            book.book(&tables, "Late", 2, Slot::at(1, 19, 30)),
+            Err(ReservationError::FullyBooked {
+                slot: Slot::at(1, 19, 30),
+                size: 2
+            })
+        );
+        // Back to back and on another day the tables are free again.
+        assert!(book.book(&tables, "Eight", 2, Slot::at(1, 20, 0)).is_ok());
+        assert!(book
+            .book(&tables, "Tomorrow", 2, Slot::at(2, 19, 0))
+            .is_ok());
+        assert_eq!(book.on_day(1).len(), 3);
+    }
+
+    #[test]
+    fn late_bookings_hold_the_table_past_midnight() {
+        let tables = inventory(&[2]);
+        let mut book = ReservationBook::with_turn_time(90);
+        book.book(&tables, "Late", 2, Slot::at(1, 23, 30)).unwrap();
+
+        assert!(book.book(&tables, "Early", 2, Slot::at(2, 0, 30)).is_err());
+        assert!(book
+            .book(&tables, "Breakfast", 2, Slot::at(2, 1, 0))
+            .is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "hour 25 is not between 0 and 23")]
+    fn slots_are_real_times() {
+        Slot::at(0, 25, 99);
+    }
+
+    #[test]
+    fn detects_double_booking_of_a_table() {
+        let tables = inventory(&[4]);
+        let mut book = ReservationBook::new();
+        let first = book
+            .book_table(&tables, 1, "First", 4, Slot::at(0, 12, 0))
+            .unwrap();
+
+        assert_eq!(
+            book.book_table(&tables, 1, "Second", 2, Slot::at(0, 12, 30)),
+            Err(ReservationError::DoubleBooked {
+                table: 1,
+                existing: first
+            })
+        );
+        assert!(matches!(
+            book.book_table(&tables, 1, "Crowd", 6, Slot::at(0, 18, 0)),
+            Err(ReservationError::Hosting(
+                HostingError::TableTooSmall { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn walk_ins_do_not_take_reserved_tables() {
+        let mut tables = inventory(&[2, 4]);
+        let mut waitlist = Waitlist::new();
+        let mut book = ReservationBook::with_turn_time(60);
+        let reserved = book
+            .book_table(&tables, 1, "Booked", 2, Slot::at(0, 19, 30))
+            .unwrap();
+        waitlist.add("Walk-in", 2).unwrap();
+        waitlist.add("Another", 2).unwrap();
+
+        let seated = book
+            .seat_walk_in(Slot::at(0, 19, 0), &mut waitlist, &mut tables)
+            .unwrap();
+        assert_eq!(seated.table, 2);
+        assert_eq!(
+            book.seat_walk_in(Slot::at(0, 19, 0), &mut waitlist, &mut tables),
+            None
+        );
+
+        let checked_in = book.check_in(reserved, &mut waitlist, &mut tables).unwrap();
+        assert_eq!(checked_in.table, 1);
+        assert_ne!(checked_in.party.id, seated.party.id);
+        assert_eq!(
+            book.get(reserved).unwrap().status,
+            ReservationStatus::Seated
+        );
+    }
+
+    #[test]
+    fn no_shows_and_cancellations_free_tables_for_the_waitlist() {
+        let mut tables = inventory(&[2, 2]);
+        let mut waitlist = Waitlist::new();
+        let mut book = ReservationBook::new();
+        let now = Slot::at(3, 18, 0);
+        let ghost = book.book(&tables, "Ghost", 2, now).unwrap();
+        let later = book.book(&tables, "Later", 2, Slot::at(3, 18, 15)).unwrap();
+        waitlist.add("Hungry", 2).unwrap();
+        waitlist.add("Patient", 1).unwrap();
+        assert_eq!(book.seat_walk_in(now, &mut waitlist, &mut tables), None);
+
+        let seated = book
+            .mark_no_show(ghost, now, &mut waitlist, &mut tables)
+            .unwrap()
+            .unwrap();
+        assert_eq!(seated.party.name, "Hungry");
+
+        let seated = book
+            .cancel(later, now, &mut waitlist, &mut tables)
+            .unwrap()
+            .unwrap();
+        assert_eq!(seated.party.name, "Patient");
+        assert!(waitlist.is_empty());
+
+        assert_eq!(
+            book.cancel(ghost, now, &mut waitlist, &mut tables),
+            Err(ReservationError::NotBooked {
+                reservation: ghost,
+                status: ReservationStatus::NoShow
+            })
+        );
+        // The cancelled slot can be booked again.
+        assert!(book
+            .book_table(&tables, 2, "Rebooked", 2, Slot::at(3, 18, 15))
+            .is_ok());
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::back_of_house::menu::Menu;
+use crate::front_of_house::billing::{Bill, BillSettings, BillingError};
+use crate::front_of_house::hosting::TableId;
+
+pub type OrderId = u32;
+
+// Where a ticket is between being ordered and being paid for.
+//
+//   Placed -> Cooking -> Ready -> Served -> Paid
+//                ^                  |
+//                +---- Remade <-----+   (fix_incorrect_order)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrderStatus {
+    Placed,
+    Cooking,
+    Ready,
+    Served,
+    Remade,
+    Paid,
+}
+
+impl OrderStatus {
+    pub fn can_move_to(self, next: OrderStatus) -> bool {
+        use OrderStatus::*;
+
+        matches!(
+            (self, next),
+            (Placed, Cooking)
+                | (Cooking, Ready)
+                | (Ready, Served)
+                | (Served, Paid)
+                | (Served, Remade)
+                | (Remade, Cooking)
+        )
+    }
+}
+
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            OrderStatus::Placed => "placed",
+            OrderStatus::Cooking => "cooking",
+            OrderStatus::Ready => "ready",
+            OrderStatus::Served => "served",
+            OrderStatus::Remade => "remade",
+            OrderStatus::Paid => "paid",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    EmptyOrder,
+    UnknownOrder(OrderId),
+    IllegalTransition {
+        order: OrderId,
+        from: OrderStatus,
+        to: OrderStatus,
+    },
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderError::EmptyOrder => write!(f, "an order needs at least one item"),
+            OrderError::UnknownOrder(id) => write!(f, "there is no order {}", id),
+            OrderError::IllegalTransition { order, from, to } => {
+                write!(f, "order {} cannot go from {} to {}", order, from, to)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OrderError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderItem {
+    pub name: String,
+    pub quantity: u32,
+    // (modifier, option) pairs such as ("toast", "Rye"), checked against the menu when the order is priced.
+    pub choices: Vec<(String, String)>,
+}
+
+impl OrderItem {
+    pub fn new(name: &str, quantity: u32) -> OrderItem {
+        OrderItem {
+            name: String::from(name),
+            quantity,
+            choices: Vec::new(),
+        }
+    }
+
+    pub fn with_choice(mut self, modifier: &str, option: &str) -> OrderItem {
+        self.choices
+            .push((String::from(modifier), String::from(option)));
+        self
+    }
+
+    pub fn choice(&self, modifier: &str) -> Option<&str> {
+        self.choices
+            .iter()
+            .find(|(name, _)| name == modifier)
+            .map(|(_, option)| option.as_str())
+    }
+}
+
+// A ticket for one table. The status can only be changed through advance, which enforces the lifecycle above.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Order {
+    id: OrderId,
+    table: TableId,
+    items: Vec<OrderItem>,
+    status: OrderStatus,
+    history: Vec<OrderStatus>,
+}
+
+impl Order {
+    pub fn id(&self) -> OrderId {
+        self.id
+    }
+
+    pub fn table(&self) -> TableId {
+        self.table
+    }
+
+    pub fn items(&self) -> &[OrderItem] {
+        &self.items
+    }
+
+    pub fn status(&self) -> OrderStatus {
+        self.status
+    }
+
+    // Every status the order has been in, oldest first, including the current one.
+    pub fn history(&self) -> &[OrderStatus] {
+        &self.history
+    }
+
+    // How many times the kitchen had to make this order again.
+    pub fn remakes(&self) -> usize {
+        self.history
+            .iter()
+            .filter(|&&status| status == OrderStatus::Remade)
+            .count()
+    }
+
+    pub fn advance(&mut self, next: OrderStatus) -> Result<(), OrderError> {
+        if !self.status.can_move_to(next) {
+            return Err(OrderError::IllegalTransition {
+                order: self.id,
+                from: self.status,
+                to: next,
+            });
+        }
+        self.status = next;
+        self.history.push(next);
+        Ok(())
+    }
+}
+
+// All the orders the restaurant has taken, by id.
+#[derive(Debug)]
+pub struct OrderBook {
+    orders: BTreeMap<OrderId, Order>,
+    next_id: OrderId,
+}
+
+impl OrderBook {
+    pub fn new() -> OrderBook {
+        OrderBook {
+            orders: BTreeMap::new(),
+            next_id: 1,
+        }
+    }
+
+    pub fn get(&self, id: OrderId) -> Result<&Order, OrderError> {
+        self.orders.get(&id).ok_or(OrderError::UnknownOrder(id))
+    }
+
+    pub fn get_mut(&mut self, id: OrderId) -> Result<&mut Order, OrderError> {
+        self.orders.get_mut(&id).ok_or(OrderError::UnknownOrder(id))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Order> {
+        self.orders.values()
+    }
+
+    pub fn with_status(&self, status: OrderStatus) -> impl Iterator<Item = &Order> {
+        self.iter().filter(move |order| order.status == status)
+    }
+}
+
+impl Default for OrderBook {
+    fn default() -> OrderBook {
+        OrderBook::new()
+    }
+}
+
+pub fn take_order(
+    book: &mut OrderBook,
+    table: TableId,
+    items: Vec<OrderItem>,
+) -> Result<OrderId, OrderError> {
+    if items.iter().all(|item| item.quantity == 0) {
+        return Err(OrderError::EmptyOrder);
+    }
+
+    let id = book.next_id;
+    book.next_id += 1;
+    book.orders.insert(
+        id,
+        Order {
+            id,
+            table,
+            items: items.into_iter().filter(|item| item.quantity > 0).collect(),
+            status: OrderStatus::Placed,
+            history: vec![OrderStatus::Placed],
+        },
+    );
+    Ok(id)
+}
+
+pub fn serve_order(order: &mut Order) -> Result<(), OrderError> {
+    order.advance(OrderStatus::Served)
+}
+
+// Works out the bill for a served order and marks it as paid. Nothing changes if the bill cannot be made.
+pub fn take_payment(
+    order: &mut Order,
+    menu: &Menu,
+    settings: &BillSettings,
+) -> Result<Bill, BillingError> {
+    if !order.status().can_move_to(OrderStatus::Paid) {
+        return Err(BillingError::Order(OrderError::IllegalTransition {
+            order: order.id(),
+            from: order.status(),
+            to: OrderStatus::Paid,
+        }));
+    }
+    let bill = Bill::for_order(order, menu, settings)?;
+    order.advance(OrderStatus::Paid)?;
+    Ok(bill)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placed() -> Order {
+        let mut book = OrderBook::new();
+        let id = take_order(&mut book, 1, vec![OrderItem::new("Soup", 2)]).unwrap();
+        book.get(id).unwrap().clone()
+    }
+
+    #[test]
+    fn follows_the_lifecycle() {
+        let mut order = placed();
+        for next in [
+            OrderStatus::Cooking,
+            OrderStatus::Ready,
+            OrderStatus::Served,
+            OrderStatus::Remade,
+            OrderStatus::Cooking,
+            OrderStatus::Ready,
+            OrderStatus::Served,
+            OrderStatus::Paid,
+        ] {
+            order.advance(next).unwrap();
+        }
+
+        assert_eq!(order.status(), OrderStatus::Paid);
+        assert_eq!(order.history().len(), 9);
+        assert_eq!(order.remakes(), 1);
+    }
+
+    #[test]
+    fn rejects_illegal_moves() {
+        let mut order = placed();
+
+        assert_eq!(
+            serve_order(&mut order),
+            Err(OrderError::IllegalTransition {
+                order: order.id(),
+                from: OrderStatus::Placed,
+                to: OrderStatus::Served,
+            })
+        );
+        let settings = BillSettings::with_tax(0);
+        assert!(take_payment(&mut order, &Menu::house(), &settings).is_err());
+        assert!(order.advance(OrderStatus::Remade).is_err());
+        assert_eq!(order.status(), OrderStatus::Placed);
+        assert_eq!(order.history(), &[OrderStatus::Placed]);
+    }
+
+    #[test]
+    fn payment_produces_a_bill() {
+        let mut order = placed();
+        for next in [
+            OrderStatus::Cooking,
+            OrderStatus::Ready,
+            OrderStatus::Served,
+        ] {
+            order.advance(next).unwrap();
+        }
+
+        let bill = take_payment(&mut order, &Menu::house(), &BillSettings::with_tax(0)).unwrap();
+        assert_eq!(bill.total(), 1300);
+        assert_eq!(order.status(), OrderStatus::Paid);
+    }
+
+    #[test]
+    fn paid_is_final() {
+        let every = [
+            OrderStatus::Placed,
+            OrderStatus::Cooking,
+            OrderStatus::Ready,
+            OrderStatus::Served,
+            OrderStatus::Remade,
+            OrderStatus::Paid,
+        ];
+        assert!(every
+            .iter()
+            .all(|&next| !OrderStatus::Paid.can_move_to(next)));
+    }
+
+    #[test]
+    fn empty_orders_are_refused() {
+        let mut book = OrderBook::new();
+        assert_eq!(
+            take_order(&mut book, 1, vec![]),
+            Err(OrderError::EmptyOrder)
+        );
+        assert_eq!(
+            take_order(&mut book, 1, vec![OrderItem::new("Salad", 0)]),
+            Err(OrderError::EmptyOrder)
+        );
+        assert_eq!(book.get(1).err(), Some(OrderError::UnknownOrder(1)));
+    }
+}
@@ -1 +1,4 @@
-pub mod vegetables;
\ No newline at end of file
+pub mod calendar; // Sowing and harvest dates worked out from each vegetable's seasons
+pub mod companions; // Which vegetables help or hinder each other
+pub mod plot; // Beds laid out as a grid of cells
+pub mod vegetables;
@@ -0,0 +1,183 @@
+// Turns the sowing seasons and growing times of vegetables into dates.
+// Days are counted from 1 January as day 1 in a year without 29 February. Harvests can fall in a later year,
+// so a day past 365 is simply the next year.
+
+use std::fmt;
+
+use super::vegetables::Vegetable;
+use crate::season::Season;
+
+pub type DayOfYear = u32;
+
+const MONTHS: [(&str, u32); 12] = [
+    ("Jan", 31),
+    ("Feb", 28),
+    ("Mar", 31),
+    ("Apr", 30),
+    ("May", 31),
+    ("Jun", 30),
+    ("Jul", 31),
+    ("Aug", 31),
+    ("Sep", 30),
+    ("Oct", 31),
+    ("Nov", 30),
+    ("Dec", 31),
+];
+
+const DAYS_IN_YEAR: DayOfYear = 365;
+
+// The first day of `month`, which runs from 1 to 12.
+fn first_day(month: u32) -> DayOfYear {
+    MONTHS[..month as usize - 1]
+        .iter()
+        .map(|&(_, days)| days)
+        .sum::<u32>()
+        + 1
+}
+
+fn season_of(day: DayOfYear) -> Season {
+    let mut day_in_year = (day - 1) % DAYS_IN_YEAR + 1;
+    for (index, &(_, days)) in MONTHS.iter().enumerate() {
+        if day_in_year <= days {
+            return Season::from_month(index as u32 + 1).expect("months run from 1 to 12");
+        }
+        day_in_year -= days;
+    }
+    unreachable!("every day of the year is in a month")
+}
+
+// A day written the way a gardener would, e.g. "Mar 1", or "Jun 2, year 2" for the following year.
+// None for day 0, which comes before the first day of the calendar.
+pub fn format_day(day: DayOfYear) -> Option<String> {
+    if day == 0 {
+        return None;
+    }
+    let year = (day - 1) / DAYS_IN_YEAR + 1;
+    let mut day_in_year = (day - 1) % DAYS_IN_YEAR + 1;
+    let mut month = "Dec";
+    for &(name, days) in &MONTHS {
+        if day_in_year <= days {
+            month = name;
+            break;
+        }
+        day_in_year -= days;
+    }
+
+    Some(if year == 1 {
+        format!("{} {}", month, day_in_year)
+    } else {
+        format!("{} {}, year {}", month, day_in_year, year)
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarEntry {
+    pub vegetable: Vegetable,
+    pub sow: DayOfYear,
+    pub harvest: DayOfYear,
+}
+
+impl fmt::Display for CalendarEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let day = |day| format_day(day).unwrap_or_else(|| format!("day {}", day));
+        write!(
+            f,
+            "{:<14} sow {:<8} harvest from {}",
+            self.vegetable,
+            day(self.sow),
+            day(self.harvest)
+        )
+    }
+}
+
+// Sowing dates for one vegetable over a year. It is first sown at the start of its earliest sowing month,
+// then again every `succession_days` while the sowing day is still in one of its seasons.
+pub fn sowings(vegetable: Vegetable) -> Vec<CalendarEntry> {
+    let profile = vegetable.profile();
+    let Some(first_month) = (1..=12).find(|&month| {
+        Season::from_month(month).is_some_and(|season| vegetable.can_sow_in(season))
+    }) else {
+        return Vec::new();
+    };
+
+    let mut entries = Vec::new();
+    let mut sow = first_day(first_month);
+    loop {
+        entries.push(CalendarEntry {
+            vegetable,
+            sow,
+            harvest: sow + profile.days_to_harvest,
+        });
+        let Some(interval) = profile.succession_days else {
+            break;
+        };
+        // Jump over seasons the vegetable skips, e.g. lettuce goes from spring straight to autumn.
+        let mut next = sow + interval;
+        while next <= DAYS_IN_YEAR && !vegetable.can_sow_in(season_of(next)) {
+            next += 1;
+        }
+        if next > DAYS_IN_YEAR {
+            break;
+        }
+        sow = next;
+    }
+    entries
+}
+
+// Every sowing of every vegetable in `vegetables`, in the order they happen.
+pub fn planting_calendar(vegetables: &[Vegetable]) -> Vec<CalendarEntry> {
+    let mut entries: Vec<_> = vegetables
+        .iter()
+        .flat_map(|&vegetable| sowings(vegetable))
+        .collect();
+    entries.sort_by_key(|entry| (entry.sow, entry.vegetable));
+    entries.dedup();
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_days_across_years() {
+        assert_eq!(format_day(1).unwrap(), "Jan 1");
+        assert_eq!(format_day(60).unwrap(), "Mar 1");
+        assert_eq!(format_day(365).unwrap(), "Dec 31");
+        assert_eq!(format_day(366 + 31).unwrap(), "Feb 1, year 2");
+        assert_eq!(format_day(0), None);
+        assert_eq!(season_of(first_day(6)), Season::Summer);
+    }
+
+    #[test]
+    fn single_and_succession_sowings() {
+        let tomato = sowings(Vegetable::Tomato);
+        assert_eq!(tomato.len(), 1);
+        assert_eq!(format_day(tomato[0].sow).unwrap(), "Mar 1");
+        assert_eq!(format_day(tomato[0].harvest).unwrap(), "May 15");
+
+        let garlic = sowings(Vegetable::Garlic);
+        assert_eq!(format_day(garlic[0].sow).unwrap(), "Sep 1");
+        assert_eq!(format_day(garlic[0].harvest).unwrap(), "Apr 29, year 2");
+
+        // Lettuce is sown every two weeks in spring, skips summer and starts again in autumn.
+        let lettuce = sowings(Vegetable::Lettuce);
+        let days: Vec<_> = lettuce.iter().map(|entry| season_of(entry.sow)).collect();
+        assert!(days.iter().all(|&season| season != Season::Summer));
+        assert!(lettuce
+            .iter()
+            .any(|entry| format_day(entry.sow).as_deref() == Some("Sep 1")));
+        assert_eq!(lettuce[1].sow - lettuce[0].sow, 14);
+    }
+
+    #[test]
+    fn calendar_is_in_sowing_order() {
+        let entries = planting_calendar(&[Vegetable::Garlic, Vegetable::Tomato, Vegetable::Tomato]);
+        let vegetables: Vec<_> = entries.iter().map(|entry| entry.vegetable).collect();
+        assert_eq!(vegetables, vec![Vegetable::Tomato, Vegetable::Garlic]);
+        assert_eq!(
+            entries[0].to_string(),
+            "Tomato         sow Mar 1    harvest from May 15"
+        );
+    }
+}
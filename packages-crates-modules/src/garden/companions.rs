@@ -0,0 +1,87 @@
+// Companion planting: some vegetables do better next to each other, some hold each other back.
+// The rules go both ways, so each pair is only listed once.
+
+use super::vegetables::Vegetable::{self, *};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Companion,
+    Neutral,
+    Antagonist,
+}
+
+const COMPANIONS: [(Vegetable, Vegetable); 10] = [
+    (Asparagus, Tomato),
+    (Basil, Tomato),
+    (Bean, Corn),
+    (Bean, Squash),
+    (Cabbage, Onion),
+    (Carrot, Lettuce),
+    (Carrot, Onion),
+    (Corn, Squash),
+    (Lettuce, Onion),
+    (Pea, Carrot),
+];
+
+const ANTAGONISTS: [(Vegetable, Vegetable); 7] = [
+    (Asparagus, Onion),
+    (Asparagus, Garlic),
+    (Bean, Garlic),
+    (Bean, Onion),
+    (Cabbage, Tomato),
+    (Corn, Tomato),
+    (Pea, Onion),
+];
+
+fn listed(pairs: &[(Vegetable, Vegetable)], a: Vegetable, b: Vegetable) -> bool {
+    pairs.iter().any(|&pair| pair == (a, b) || pair == (b, a))
+}
+
+pub fn relation(a: Vegetable, b: Vegetable) -> Relation {
+    if listed(&COMPANIONS, a, b) {
+        Relation::Companion
+    } else if listed(&ANTAGONISTS, a, b) {
+        Relation::Antagonist
+    } else {
+        Relation::Neutral
+    }
+}
+
+fn related(vegetable: Vegetable, wanted: Relation) -> Vec<Vegetable> {
+    Vegetable::ALL
+        .iter()
+        .copied()
+        .filter(|&other| relation(vegetable, other) == wanted)
+        .collect()
+}
+
+pub fn companions_of(vegetable: Vegetable) -> Vec<Vegetable> {
+    related(vegetable, Relation::Companion)
+}
+
+pub fn antagonists_of(vegetable: Vegetable) -> Vec<Vegetable> {
+    related(vegetable, Relation::Antagonist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_go_both_ways() {
+        assert_eq!(relation(Tomato, Basil), Relation::Companion);
+        assert_eq!(relation(Basil, Tomato), Relation::Companion);
+        assert_eq!(relation(Onion, Bean), Relation::Antagonist);
+        assert_eq!(relation(Tomato, Tomato), Relation::Neutral);
+
+        assert_eq!(companions_of(Squash), vec![Bean, Corn]);
+        assert_eq!(antagonists_of(Onion), vec![Asparagus, Bean, Pea]);
+    }
+
+    #[test]
+    fn no_pair_is_both_friend_and_foe() {
+        for &(a, b) in &COMPANIONS {
+            assert!(!listed(&ANTAGONISTS, a, b), "{} and {}", a, b);
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::calendar::{self, CalendarEntry};
+use super::companions::{self, Relation};
+use super::vegetables::Vegetable;
+
+pub type PlantingId = u32;
+
+// A square block of cells given to one vegetable. Vegetables spaced wider than a cell take several cells
+// in each direction, closely spaced ones fit several plants in one cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Planting {
+    pub id: PlantingId,
+    pub vegetable: Vegetable,
+    pub row: usize,
+    pub col: usize,
+    // Cells per side of the block.
+    pub size: usize,
+    pub plants: u32,
+}
+
+impl Planting {
+    // One past the last row and column of the block. Saturating, a block that far out never fits a plot anyway.
+    fn end_row(&self) -> usize {
+        self.row.saturating_add(self.size)
+    }
+
+    fn end_col(&self) -> usize {
+        self.col.saturating_add(self.size)
+    }
+
+    fn covers(&self, row: usize, col: usize) -> bool {
+        (self.row..self.end_row()).contains(&row) && (self.col..self.end_col()).contains(&col)
+    }
+
+    // Whether the blocks share an edge or a corner.
+    fn touches(&self, other: &Planting) -> bool {
+        self.row <= other.end_row()
+            && other.row <= self.end_row()
+            && self.col <= other.end_col()
+            && other.col <= self.end_col()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlotError {
+    OutOfBounds {
+        vegetable: Vegetable,
+        row: usize,
+        col: usize,
+    },
+    Occupied {
+        row: usize,
+        col: usize,
+        by: Vegetable,
+    },
+    BadNeighbour {
+        vegetable: Vegetable,
+        neighbour: Vegetable,
+    },
+    NoRoom(Vegetable),
+    ZeroCellSize,
+}
+
+impl fmt::Display for PlotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlotError::OutOfBounds {
+                vegetable,
+                row,
+                col,
+            } => write!(
+                f,
+                "{} planted at row {}, column {} would not fit in the plot",
+                vegetable, row, col
+            ),
+            PlotError::Occupied { row, col, by } => {
+                write!(f, "row {}, column {} already has {}", row, col, by)
+            }
+            PlotError::BadNeighbour {
+                vegetable,
+                neighbour,
+            } => write!(f, "{} does not grow well next to {}", vegetable, neighbour),
+            PlotError::NoRoom(vegetable) => write!(f, "there is no room left for {}", vegetable),
+            PlotError::ZeroCellSize => write!(f, "cells must be at least 1 cm wide"),
+        }
+    }
+}
+
+impl std::error::Error for PlotError {}
+
+// A bed laid out as a grid of square cells, `cell_cm` wide.
+#[derive(Debug, Clone)]
+pub struct Plot {
+    rows: usize,
+    cols: usize,
+    cell_cm: u32,
+    plantings: BTreeMap<PlantingId, Planting>,
+    next_id: PlantingId,
+}
+
+impl Plot {
+    pub fn new(rows: usize, cols: usize, cell_cm: u32) -> Result<Plot, PlotError> {
+        if cell_cm == 0 {
+            return Err(PlotError::ZeroCellSize);
+        }
+
+        Ok(Plot {
+            rows,
+            cols,
+            cell_cm,
+            plantings: BTreeMap::new(),
+            next_id: 1,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn cell_cm(&self) -> u32 {
+        self.cell_cm
+    }
+
+    // Cells per side a plant of `vegetable` needs.
+    pub fn footprint(&self, vegetable: Vegetable) -> usize {
+        vegetable.spacing_cm().div_ceil(self.cell_cm).max(1) as usize
+    }
+
+    // How many plants go in one block of `vegetable`.
+    pub fn plants_per_block(&self, vegetable: Vegetable) -> u32 {
+        let per_side = (self.cell_cm / vegetable.spacing_cm()).max(1);
+        per_side * per_side
+    }
+
+    pub fn get(&self, id: PlantingId) -> Option<&Planting> {
+        self.plantings.get(&id)
+    }
+
+    pub fn plantings(&self) -> impl Iterator<Item = &Planting> {
+        self.plantings.values()
+    }
+
+    pub fn at(&self, row: usize, col: usize) -> Option<&Planting> {
+        self.plantings
+            .values()
+            .find(|planting| planting.covers(row, col))
+    }
+
+    // Plants a block of `vegetable` with its top left corner at `row`, `col`.
+    // The block has to fit, the cells have to be empty, and no touching planting may be an antagonist.
+    pub fn place(
+        &mut self,
+        vegetable: Vegetable,
+        row: usize,
+        col: usize,
+    ) -> Result<PlantingId, PlotError> {
+        let planting = Planting {
+            id: self.next_id,
+            vegetable,
+            row,
+            col,
+            size: self.footprint(vegetable),
+            plants: self.plants_per_block(vegetable),
+        };
+        self.check(&planting)?;
+
+        self.next_id += 1;
+        self.plantings.insert(planting.id, planting);
+        Ok(self.next_id - 1)
+    }
+
+    // Plants `vegetable` in the first spot it fits, reading the plot row by row.
+    // Spots next to companions are preferred.
+    pub fn place_anywhere(&mut self, vegetable: Vegetable) -> Result<PlantingId, PlotError> {
+        let size = self.footprint(vegetable);
+        let mut best = None;
+        for row in 0..=self.rows.saturating_sub(size) {
+            for col in 0..=self.cols.saturating_sub(size) {
+                let candidate = Planting {
+                    id: self.next_id,
+                    vegetable,
+                    row,
+                    col,
+                    size,
+                    plants: 0,
+                };
+                if self.check(&candidate).is_err() {
+                    continue;
+                }
+                let friends = self
+                    .touching(&candidate)
+                    .filter(|other| {
+                        companions::relation(vegetable, other.vegetable) == Relation::Companion
+                    })
+                    .count();
+                if best.is_none_or(|(most, _, _)| friends > most) {
+                    best = Some((friends, row, col));
+                }
+            }
+        }
+
+        let (_, row, col) = best.ok_or(PlotError::NoRoom(vegetable))?;
+        self.place(vegetable, row, col)
+    }
+
+    pub fn remove(&mut self, id: PlantingId) -> Option<Planting> {
+        self.plantings.remove(&id)
+    }
+
+    // Plantings whose blocks touch the block of `id`, corners included.
+    pub fn neighbours(&self, id: PlantingId) -> Vec<&Planting> {
+        match self.plantings.get(&id) {
+            Some(planting) => self.touching(planting).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // One point for every pair of touching companions. Antagonists can not be placed next to each other.
+    pub fn companion_score(&self) -> usize {
+        let plantings: Vec<_> = self.plantings.values().collect();
+        let mut score = 0;
+        for (index, a) in plantings.iter().enumerate() {
+            for b in &plantings[index + 1..] {
+                if a.touches(b)
+                    && companions::relation(a.vegetable, b.vegetable) == Relation::Companion
+                {
+                    score += 1;
+                }
+            }
+        }
+        score
+    }
+
+    pub fn vegetables(&self) -> Vec<Vegetable> {
+        let distinct: BTreeSet<_> = self
+            .plantings
+            .values()
+            .map(|planting| planting.vegetable)
+            .collect();
+        distinct.into_iter().collect()
+    }
+
+    // When to sow and harvest everything growing in the plot.
+    pub fn calendar(&self) -> Vec<CalendarEntry> {
+        calendar::planting_calendar(&self.vegetables())
+    }
+
+    fn touching<'a>(&'a self, planting: &'a Planting) -> impl Iterator<Item = &'a Planting> {
+        self.plantings
+            .values()
+            .filter(move |other| other.id != planting.id && other.touches(planting))
+    }
+
+    fn check(&self, planting: &Planting) -> Result<(), PlotError> {
+        let fits = |start: usize, limit| {
+            start
+                .checked_add(planting.size)
+                .is_some_and(|end| end <= limit)
+        };
+        if !fits(planting.row, self.rows) || !fits(planting.col, self.cols) {
+            return Err(PlotError::OutOfBounds {
+                vegetable: planting.vegetable,
+                row: planting.row,
+                col: planting.col,
+            });
+        }
+        for row in planting.row..planting.end_row() {
+            for col in planting.col..planting.end_col() {
+                if let Some(other) = self.at(row, col) {
+                    return Err(PlotError::Occupied {
+                        row,
+                        col,
+                        by: other.vegetable,
+                    });
+                }
+            }
+        }
+        if let Some(other) = self.touching(planting).find(|other| {
+            companions::relation(planting.vegetable, other.vegetable) == Relation::Antagonist
+        }) {
+            return Err(PlotError::BadNeighbour {
+                vegetable: planting.vegetable,
+                neighbour: other.vegetable,
+            });
+        }
+        Ok(())
+    }
+}
+
+// Draws the plot one character per cell, '.' for empty cells, followed by a legend.
+impl fmt::Display for Plot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in 0..self.rows {
+            let line: String = (0..self.cols)
+                .map(|col| {
+                    self.at(row, col)
+                        .map_or('.', |planting| planting.vegetable.symbol())
+                })
+                .collect();
+            writeln!(f, "{}", line)?;
+        }
+        for vegetable in self.vegetables() {
+            writeln!(f, "{} {}", vegetable.symbol(), vegetable)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_follow_spacing() {
+        let mut plot = Plot::new(4, 4, 30).unwrap();
+        let tomato = plot.place(Vegetable::Tomato, 0, 0).unwrap();
+        let carrots = plot.place(Vegetable::Carrot, 3, 3).unwrap();
+
+        assert_eq!(plot.get(tomato).unwrap().size, 2);
+        assert_eq!(plot.get(carrots).unwrap().plants, 9);
+        assert_eq!(plot.at(1, 1).unwrap().vegetable, Vegetable::Tomato);
+        assert_eq!(plot.at(2, 2), None);
+        assert_eq!(
+            plot.place(Vegetable::Squash, 1, 1),
+            Err(PlotError::Occupied {
+                row: 1,
+                col: 1,
+                by: Vegetable::Tomato
+            })
+        );
+        assert!(matches!(
+            plot.place(Vegetable::Tomato, 3, 0),
+            Err(PlotError::OutOfBounds { .. })
+        ));
+        assert!(matches!(
+            plot.place(Vegetable::Tomato, usize::MAX, 0),
+            Err(PlotError::OutOfBounds { .. })
+        ));
+        assert!(matches!(
+            plot.place(Vegetable::Carrot, 0, usize::MAX),
+            Err(PlotError::OutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn antagonists_are_kept_apart() {
+        let mut plot = Plot::new(3, 5, 30).unwrap();
+        plot.place(Vegetable::Onion, 0, 0).unwrap();
+
+        assert_eq!(
+            plot.place(Vegetable::Bean, 1, 1),
+            Err(PlotError::BadNeighbour {
+                vegetable: Vegetable::Bean,
+                neighbour: Vegetable::Onion
+            })
+        );
+        assert!(plot.place(Vegetable::Bean, 0, 2).is_ok());
+    }
+
+    #[test]
+    fn place_anywhere_looks_for_companions() {
+        let mut plot = Plot::new(1, 6, 60).unwrap();
+        plot.place(Vegetable::Tomato, 0, 5).unwrap();
+        let basil = plot.place_anywhere(Vegetable::Basil).unwrap();
+
+        assert_eq!(plot.get(basil).unwrap().col, 4);
+        assert_eq!(plot.companion_score(), 1);
+        assert_eq!(plot.neighbours(basil).len(), 1);
+        assert_eq!(plot.to_string(), "....bT\nb Basil\nT Tomato\n");
+
+        let mut full = Plot::new(1, 1, 30).unwrap();
+        full.place(Vegetable::Lettuce, 0, 0).unwrap();
+        assert_eq!(
+            full.place_anywhere(Vegetable::Pea),
+            Err(PlotError::NoRoom(Vegetable::Pea))
+        );
+        assert_eq!(Plot::new(1, 1, 0).unwrap_err(), PlotError::ZeroCellSize);
+    }
+}
@@ -1,2 +1,151 @@
-#[derive(Debug)]
-pub struct Asparagus {}
\ No newline at end of file
+use std::fmt;
+use std::str::FromStr;
+
+use crate::season::Season;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Vegetable {
+    Asparagus,
+    Basil,
+    Bean,
+    Cabbage,
+    Carrot,
+    Corn,
+    Garlic,
+    Lettuce,
+    Onion,
+    Pea,
+    Squash,
+    Tomato,
+}
+
+// What a gardener needs to know to plant a vegetable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    // Distance between two plants, in both directions.
+    pub spacing_cm: u32,
+    // From sowing to the first harvest. Perennials like asparagus take more than a year.
+    pub days_to_harvest: u32,
+    // Seasons the vegetable can be sown in.
+    pub sowing_seasons: &'static [Season],
+    // Quick crops are sown again every so many days while their season lasts, so the harvest does not come all at once.
+    pub succession_days: Option<u32>,
+}
+
+impl Vegetable {
+    pub const ALL: [Vegetable; 12] = [
+        Vegetable::Asparagus,
+        Vegetable::Basil,
+        Vegetable::Bean,
+        Vegetable::Cabbage,
+        Vegetable::Carrot,
+        Vegetable::Corn,
+        Vegetable::Garlic,
+        Vegetable::Lettuce,
+        Vegetable::Onion,
+        Vegetable::Pea,
+        Vegetable::Squash,
+        Vegetable::Tomato,
+    ];
+
+    pub fn profile(self) -> Profile {
+        use Season::*;
+
+        let (spacing_cm, days_to_harvest, sowing_seasons, succession_days): (_, _, &[Season], _) =
+            match self {
+                Vegetable::Asparagus => (45, 730, &[Spring], None),
+                Vegetable::Basil => (25, 60, &[Spring, Summer], Some(28)),
+                Vegetable::Bean => (15, 60, &[Spring, Summer], Some(21)),
+                Vegetable::Cabbage => (45, 90, &[Spring, Summer], None),
+                Vegetable::Carrot => (8, 70, &[Spring, Summer], Some(21)),
+                Vegetable::Corn => (30, 85, &[Spring], None),
+                Vegetable::Garlic => (15, 240, &[Autumn], None),
+                Vegetable::Lettuce => (25, 45, &[Spring, Autumn], Some(14)),
+                Vegetable::Onion => (10, 100, &[Spring], None),
+                Vegetable::Pea => (8, 65, &[Spring], Some(14)),
+                Vegetable::Squash => (90, 95, &[Spring], None),
+                Vegetable::Tomato => (60, 75, &[Spring], None),
+            };
+
+        Profile {
+            spacing_cm,
+            days_to_harvest,
+            sowing_seasons,
+            succession_days,
+        }
+    }
+
+    pub fn spacing_cm(self) -> u32 {
+        self.profile().spacing_cm
+    }
+
+    pub fn days_to_harvest(self) -> u32 {
+        self.profile().days_to_harvest
+    }
+
+    pub fn can_sow_in(self, season: Season) -> bool {
+        self.profile().sowing_seasons.contains(&season)
+    }
+
+    // The letter the vegetable is drawn with on a plot. First letters clash, so some are picked by hand.
+    pub fn symbol(self) -> char {
+        match self {
+            Vegetable::Asparagus => 'A',
+            Vegetable::Basil => 'b',
+            Vegetable::Bean => 'B',
+            Vegetable::Cabbage => 'K',
+            Vegetable::Carrot => 'C',
+            Vegetable::Corn => 'Z',
+            Vegetable::Garlic => 'G',
+            Vegetable::Lettuce => 'L',
+            Vegetable::Onion => 'O',
+            Vegetable::Pea => 'P',
+            Vegetable::Squash => 'S',
+            Vegetable::Tomato => 'T',
+        }
+    }
+}
+
+impl fmt::Display for Vegetable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // pad rather than write!, so widths like {:<14} are honoured when lining up tables.
+        f.pad(&format!("{:?}", self))
+    }
+}
+
+impl FromStr for Vegetable {
+    type Err = String;
+
+    // Accepts names in any case, e.g. "tomato" or "Tomato".
+    fn from_str(name: &str) -> Result<Vegetable, String> {
+        Vegetable::ALL
+            .iter()
+            .copied()
+            .find(|vegetable| vegetable.to_string().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("unknown vegetable {:?}", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_and_knows_the_seasons() {
+        assert_eq!("tomato".parse(), Ok(Vegetable::Tomato));
+        assert_eq!(" Garlic ".parse(), Ok(Vegetable::Garlic));
+        assert!("kale".parse::<Vegetable>().is_err());
+
+        assert!(Vegetable::Garlic.can_sow_in(Season::Autumn));
+        assert!(!Vegetable::Tomato.can_sow_in(Season::Winter));
+        assert!(Vegetable::Asparagus.days_to_harvest() > 365);
+    }
+
+    #[test]
+    fn symbols_are_unique() {
+        let mut symbols: Vec<char> = Vegetable::ALL.iter().map(|v| v.symbol()).collect();
+        symbols.sort_unstable();
+        symbols.dedup();
+        assert_eq!(symbols.len(), Vegetable::ALL.len());
+    }
+}
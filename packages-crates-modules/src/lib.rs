@@ -1,33 +1,89 @@
 mod front_of_house {
-    pub mod hosting {
-        pub fn add_to_waitlist() {}
+    pub mod hosting; // Sibling module to serving defined within front_of_house
+    // The body of hosting lives in src/front_of_house/hosting.rs. Declaring a module with a semicolon instead of a block tells Rust to load it from that file.
 
-        fn seat_at_table() {}
-    } // Sibling module to serving defined within front_of_house
+    pub mod serving; // Sibling module to hosting defined within front_of_house
 
-    mod serving {
-        fn take_order() {}
+    pub mod billing; // Bills, tax, tips and splitting, used by serving::take_payment
 
-        fn serve_order() {}
-
-        fn take_payment() {}
-    } // Sibling module to hosting defined within front_of_house
+    pub mod reservations; // Tables booked ahead of time, sharing hosting's TableInventory
 }
 
 mod back_of_house {
     use crate::front_of_house::hosting;
 
-    fn fix_incorrect_order() {
-        cook_order();
-        super::deliver_order(); //  super works like `..` in the filesystem. It allows us to reference an item that we know is a parent module.
+    use super::front_of_house::serving::{Order, OrderError, OrderStatus}; //  super works like `..` in the filesystem. It allows us to reference an item that we know is a parent module.
+    use self::kitchen::{Kitchen, KitchenError, Priority};
+    use self::menu::Menu;
+
+    // Sends a served order that was wrong back to the kitchen, where it jumps the queue.
+    pub fn fix_incorrect_order(
+        kitchen: &mut Kitchen,
+        menu: &Menu,
+        order: &mut Order,
+    ) -> Result<(), KitchenError> {
+        order.advance(OrderStatus::Remade)?;
+        cook_order(kitchen, menu, order)?;
+        Ok(())
+    }
+
+    // Puts a ticket for every item on the kitchen display. Remade orders are cooked before new ones.
+    // If the kitchen keeps an inventory, the ingredients are taken from it, and an order it cannot make is refused
+    // before anything is queued.
+    pub fn cook_order(
+        kitchen: &mut Kitchen,
+        menu: &Menu,
+        order: &mut Order,
+    ) -> Result<(), KitchenError> {
+        if !order.status().can_move_to(OrderStatus::Cooking) {
+            return Err(KitchenError::Order(OrderError::IllegalTransition {
+                order: order.id(),
+                from: order.status(),
+                to: OrderStatus::Cooking,
+            }));
+        }
+        let priority = match order.status() {
+            OrderStatus::Remade => Priority::Remake,
+            _ => Priority::Normal,
+        };
+        let needs = match kitchen.inventory() {
+            Some(inventory) => Some(inventory.check(order)?),
+            None => None,
+        };
+        kitchen.enqueue(order, menu, priority)?;
+        if let (Some(needs), Some(inventory)) = (needs, kitchen.inventory_mut()) {
+            inventory.take(&needs);
+        }
+        order.advance(OrderStatus::Cooking)?;
+        Ok(())
+    }
+
+    // The kitchen calls "order up!" when a dish is ready to be taken to the table.
+    pub fn order_up(order: &mut Order) -> Result<(), OrderError> {
+        order.advance(OrderStatus::Ready)
     }
 
-    fn cook_order() {}
+    pub mod inventory; // Recipes and ingredient stock, loaded from pantry.toml
+    pub mod kitchen; // Ticket queues per station, run on a simulated clock
+    pub mod menu; // Priced items, categories and modifiers, in src/back_of_house/menu.rs
+    use crate::season::Season;
+    use crate::front_of_house::serving::OrderItem;
 
     pub enum Appetizer {
         Soup,
         Salad,
     } // In contrast to structs, if we make an enum public, all of its variants are then public.
+
+    impl Appetizer {
+        // The name the appetizer has on the menu.
+        pub fn menu_name(&self) -> &'static str {
+            match self {
+                Appetizer::Soup => "Soup",
+                Appetizer::Salad => "Salad",
+            }
+        }
+    }
+
     pub struct Breakfast {
         pub toast: String,
         seasonal_fruit: String,
@@ -35,41 +91,136 @@ mod back_of_house {
 
     impl Breakfast {
         pub fn summer(toast: &str) -> Breakfast {
+            Breakfast::in_season(toast, Season::Summer)
+        }
+
+        // The fruit is picked by the kitchen from what is in season, the guest only gets to choose the toast.
+        pub fn in_season(toast: &str, season: Season) -> Breakfast {
             Breakfast {
                 toast: String::from(toast),
-                seasonal_fruit: String::from("peaches")
+                seasonal_fruit: String::from(season.fruit()),
             }
         }
+
+        // seasonal_fruit is private, so code outside back_of_house can read it but not change it.
+        pub fn seasonal_fruit(&self) -> &str {
+            &self.seasonal_fruit
+        }
+
+        // The fruit goes on the order too, so the kitchen takes that fruit out of stock and not whatever is in season today.
+        pub fn order_item(&self) -> OrderItem {
+            OrderItem::new("Breakfast", 1)
+                .with_choice("toast", &self.toast)
+                .with_choice(inventory::FRUIT_MODIFIER, &self.seasonal_fruit)
+        }
     }
 
     // hosting::add_to_waitlist(); // This is an error because this shortcut no longer applies to the current scope.
 }
 
+pub mod simulation; // A whole evening on a simulated clock, run by src/bin/simulation.rs
+
+pub mod delivery; // Drivers and addresses for orders that leave the restaurant, see deliver_order below
+
+pub mod garden; // Loaded from src/garden.rs, whose own submodules live in src/garden/
+
+pub mod printing; // Receipts, kitchen tickets and JSON for the point of sale
+
+pub mod season; // Seasons of the year, used by both the menu and the garden
+
 pub use crate::front_of_house::hosting;
+pub use crate::front_of_house::serving;
+pub use crate::front_of_house::billing;
+pub use crate::front_of_house::reservations;
+pub use crate::back_of_house::{cook_order, fix_incorrect_order, order_up};
+pub use crate::back_of_house::{inventory, kitchen, menu, Appetizer, Breakfast};
 // Above is an example of a re-export. When we bring a name into scope with pub use, we're allowed to re-export that name to make it public. This technique is most commonly used to bring an item into scope and make it public, but also to rename items or to combine two items into one public item.
 // Now there is no need to mark the hosting module as public, because we're re-exporting it as public in the crate root.
 use crate::front_of_house::hosting::add_to_waitlist; // Idiomatic use paths
 
 pub fn eat_at_restaurant() {
+    let mut waitlist = hosting::Waitlist::new();
+    let mut tables = hosting::TableInventory::new();
+    tables.add_table(2);
+    tables.add_table(4);
+
     // Absolute path
-    crate::front_of_house::hosting::add_to_waitlist();
+    crate::front_of_house::hosting::add_to_waitlist(&mut waitlist, "Ferris", 2).unwrap();
 
     // Relative path
-    front_of_house::hosting::add_to_waitlist();
+    front_of_house::hosting::add_to_waitlist(&mut waitlist, "Corro", 4).unwrap();
 
     // use keyword to shorten path
-    hosting::add_to_waitlist();
+    hosting::add_to_waitlist(&mut waitlist, "Bors", 3).unwrap();
 
     // idiomatic use paths
-    add_to_waitlist();
+    add_to_waitlist(&mut waitlist, "Crab", 1).unwrap();
+
+    while let Some(seating) = hosting::seat_at_table(&mut waitlist, &mut tables) {
+        println!(
+            "Seated {} (party of {}) at table {}",
+            seating.party.name, seating.party.size, seating.table
+        );
+    }
+    for party in waitlist.iter() {
+        let wait = waitlist.estimated_wait(party.id, &tables).unwrap_or(0);
+        println!("{} is waiting, about {} minutes", party.name, wait);
+    }
 
     let mut meal = back_of_house::Breakfast::summer("Rye");
 
     meal.toast = String::from("Wheat");
     println!("I would like {} toast please", meal.toast);
+    println!("It comes with {}", meal.seasonal_fruit());
 
     let order1 = back_of_house::Appetizer::Soup;
     let order2 = back_of_house::Appetizer::Salad;
+
+    let menu = menu::Menu::house();
+    let items = vec![
+        meal.order_item(),
+        serving::OrderItem::new(order1.menu_name(), 1),
+        serving::OrderItem::new(order2.menu_name(), 1).with_choice("dressing", "Ranch"),
+    ];
+    for item in &items {
+        let price = menu.line_price(item).unwrap();
+        println!("{} x{}: {}", item.name, item.quantity, billing::format_cents(price));
+    }
+
+    let mut orders = serving::OrderBook::new();
+    let id = serving::take_order(&mut orders, 1, items).unwrap();
+    let order = orders.get_mut(id).unwrap();
+    let mut kitchen = kitchen::Kitchen::with_inventory(inventory::Inventory::house());
+    cook_order(&mut kitchen, &menu, order).unwrap();
+    kitchen.run_until_idle();
+    order_up(order).unwrap();
+    serving::serve_order(order).unwrap();
+    fix_incorrect_order(&mut kitchen, &menu, order).unwrap(); // The soup was cold.
+    kitchen.run_until_idle();
+    order_up(order).unwrap();
+    serving::serve_order(order).unwrap();
+    println!("The kitchen closed after {} minutes", kitchen.now());
+    for reorder in kitchen.inventory().unwrap().reorder_report() {
+        println!("Reorder {}", reorder);
+    }
+    let mut settings = billing::BillSettings::with_tax(825);
+    settings.tip_cents = 400;
+    let bill = serving::take_payment(order, &menu, &settings).unwrap();
+    println!("Order {} went through {:?}", order.id(), order.history());
+    print!(
+        "{}",
+        printing::receipt("Ferris's Diner", order, &bill, printing::RECEIPT_WIDTH)
+    );
+    for (diner, share) in bill.split_evenly(2).unwrap().into_iter().enumerate() {
+        println!("Diner {} pays {}", diner + 1, billing::format_cents(share));
+    }
 }
 
-fn deliver_order() {}
\ No newline at end of file
+// Sends a cooked order that was booked for delivery out with a driver. See the delivery module for the rest of its trip.
+pub fn deliver_order<'a>(
+    dispatch: &'a mut delivery::Dispatch,
+    order: &serving::Order,
+    now: kitchen::Minutes,
+) -> Result<&'a delivery::Delivery, delivery::DeliveryError> {
+    dispatch.dispatch(order, now)
+}
\ No newline at end of file
@@ -10,13 +10,26 @@ use std::io::Result as IoResult; //  as keyword allows us to bring in two items
 // While bringing in strcuts, enums, and other items with use, its idiomatic to specify the full path to the item. This makes it easier to tell where the item is defined.
 use std::collections::*; // The glob operator * brings all public items defined in the collections module into the current scope. This is useful when you want to bring many items into scope and you don't want to list them all out manually.
 
-use crate::garden::vegetables::Asparagus;
+use packages_crates_modules::garden::plot::Plot;
+use packages_crates_modules::garden::vegetables::Vegetable;
 
-pub mod garden;
+// The garden module used to be declared here with `pub mod garden;`. It now lives in the library next to the restaurant,
+// so the binary reaches it through the crate name like any other user of the library would.
 fn main( ) {
-    let plant = Asparagus {};
+    let plant = Vegetable::Asparagus;
 println!("Im growing {plant:?}!");
 
+let mut plot = Plot::new(4, 6, 30).unwrap();
+for vegetable in [plant, Vegetable::Tomato, Vegetable::Basil, Vegetable::Carrot, Vegetable::Onion] {
+    if let Err(error) = plot.place_anywhere(vegetable) {
+        println!("{}", error);
+    }
+}
+print!("{}", plot);
+for entry in plot.calendar() {
+    println!("{}", entry);
+}
+
 eat_at_restaurant();
 
 let mut map = HashMap::new();
@@ -0,0 +1,373 @@
+// Orders and bills turned into something to hand over: receipts for a thermal printer, tickets for the kitchen
+// and JSON for the point of sale system.
+//
+// Printers count columns, not bytes or chars. "é" is two bytes but one column, "ラ" is three bytes and two columns,
+// so every width here is measured with unicode-width.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::back_of_house::kitchen::Station;
+use crate::back_of_house::menu::{Cents, Menu};
+use crate::front_of_house::billing::{format_cents, BasisPoints, Bill};
+use crate::front_of_house::hosting::TableId;
+use crate::front_of_house::serving::{Order, OrderId};
+
+// The paper most receipt printers take fits 42 characters of the standard font.
+pub const RECEIPT_WIDTH: usize = 42;
+
+// Columns `text` takes up when printed.
+pub fn text_width(text: &str) -> usize {
+    text.width()
+}
+
+// `text` cut down to at most `width` columns. A cut is marked with "…", and a wide character is never split in half.
+pub fn truncate(text: &str, width: usize) -> String {
+    if text_width(text) <= width {
+        return String::from(text);
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut cut = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let c_width = c.width().unwrap_or(0);
+        if used + c_width > width - 1 {
+            break;
+        }
+        cut.push(c);
+        used += c_width;
+    }
+    cut.push('…');
+    cut
+}
+
+// `left` and `right` on one line of exactly `width` columns, `right` flush against the edge.
+// If they do not both fit, `left` is cut short, and `right` too if it is wider than the whole line.
+pub fn columns(left: &str, right: &str, width: usize) -> String {
+    let right = truncate(right, width);
+    let right_width = text_width(&right);
+    let room = width.saturating_sub(right_width + 1);
+    let left = truncate(left, room);
+    let gap = width.saturating_sub(text_width(&left) + right_width);
+    format!("{}{}{}", left, " ".repeat(gap), right)
+}
+
+// `text` in the middle of a line `width` columns wide, cut if it is too long.
+pub fn centre(text: &str, width: usize) -> String {
+    let text = truncate(text, width);
+    let space = width - text_width(&text);
+    format!(
+        "{}{}{}",
+        " ".repeat(space / 2),
+        text,
+        " ".repeat(space - space / 2)
+    )
+}
+
+// "8.25%" for 825 basis points.
+fn format_rate(rate: BasisPoints) -> String {
+    format!("{}.{:02}%", rate / 100, rate % 100)
+}
+
+// A customer receipt for `bill`, every line exactly `width` columns wide.
+pub fn receipt(restaurant: &str, order: &Order, bill: &Bill, width: usize) -> String {
+    let mut lines = vec![
+        centre(restaurant, width),
+        centre(
+            &format!("Order {}, table {}", order.id(), order.table()),
+            width,
+        ),
+        "-".repeat(width),
+    ];
+
+    for line in bill.lines() {
+        lines.push(columns(
+            &format!("{} x {}", line.quantity, line.name),
+            &format_cents(line.total_cents),
+            width,
+        ));
+        for choice in &line.choices {
+            lines.push(columns(&format!("    {}", choice), "", width));
+        }
+        if line.quantity > 1 {
+            lines.push(columns(
+                &format!("    @ {} each", format_cents(line.unit_cents)),
+                "",
+                width,
+            ));
+        }
+    }
+
+    lines.push("-".repeat(width));
+    lines.push(columns("Subtotal", &format_cents(bill.subtotal()), width));
+    if bill.discount() > 0 {
+        lines.push(columns(
+            "Discount",
+            &format!("-{}", format_cents(bill.discount())),
+            width,
+        ));
+    }
+    lines.push(columns(
+        &format!("Tax {}", format_rate(bill.tax_rate())),
+        &format_cents(bill.tax()),
+        width,
+    ));
+    if bill.tip() > 0 {
+        lines.push(columns("Tip", &format_cents(bill.tip()), width));
+    }
+    lines.push("=".repeat(width));
+    lines.push(columns("TOTAL", &format_cents(bill.total()), width));
+
+    lines.join("\n") + "\n"
+}
+
+// What the cooks see: the items grouped by the station that makes them, with every choice spelled out.
+// Items the menu does not know are listed last, so nothing on the order goes missing.
+pub fn kitchen_ticket(order: &Order, menu: &Menu) -> String {
+    let mut header = format!("ORDER {}  TABLE {}", order.id(), order.table());
+    if order.remakes() > 0 {
+        header.push_str(&format!("  REMAKE #{}", order.remakes()));
+    }
+    let mut lines = vec![header];
+
+    let station_of = |name: &str| menu.item(name).map(|item| item.station());
+    let groups = Station::ALL
+        .iter()
+        .map(|&station| Some(station))
+        .chain([None]);
+    for group in groups {
+        let items: Vec<_> = order
+            .items()
+            .iter()
+            .filter(|item| station_of(&item.name) == group)
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+
+        match group {
+            Some(station) => lines.push(format!("[{}]", station)),
+            None => lines.push(String::from("[not on the menu]")),
+        }
+        for item in items {
+            lines.push(format!("  {} x {}", item.quantity, item.name));
+            for (modifier, option) in &item.choices {
+                lines.push(format!("      {}: {}", modifier, option));
+            }
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[derive(Serialize)]
+struct OrderJson<'a> {
+    id: OrderId,
+    table: TableId,
+    status: String,
+    history: Vec<String>,
+    items: Vec<ItemJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct ItemJson<'a> {
+    name: &'a str,
+    quantity: u32,
+    choices: BTreeMap<&'a str, &'a str>,
+}
+
+#[derive(Serialize)]
+struct BillJson<'a> {
+    order: OrderId,
+    lines: Vec<BillLineJson<'a>>,
+    subtotal_cents: Cents,
+    discount_cents: Cents,
+    tax_rate_basis_points: BasisPoints,
+    tax_cents: Cents,
+    tip_cents: Cents,
+    total_cents: Cents,
+}
+
+#[derive(Serialize)]
+struct BillLineJson<'a> {
+    name: &'a str,
+    choices: &'a [String],
+    quantity: u32,
+    unit_cents: Cents,
+    total_cents: Cents,
+}
+
+// The order as JSON, with statuses as the lowercase names Display gives them.
+pub fn order_json(order: &Order) -> String {
+    let json = OrderJson {
+        id: order.id(),
+        table: order.table(),
+        status: order.status().to_string(),
+        history: order
+            .history()
+            .iter()
+            .map(|status| status.to_string())
+            .collect(),
+        items: order
+            .items()
+            .iter()
+            .map(|item| ItemJson {
+                name: &item.name,
+                quantity: item.quantity,
+                choices: item
+                    .choices
+                    .iter()
+                    .map(|(modifier, option)| (modifier.as_str(), option.as_str()))
+                    .collect(),
+            })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&json).expect("an order always serializes")
+}
+
+// The bill as JSON. Amounts stay in cents so the point of sale never has to parse "$12.05".
+pub fn bill_json(bill: &Bill) -> String {
+    let json = BillJson {
+        order: bill.order(),
+        lines: bill
+            .lines()
+            .iter()
+            .map(|line| BillLineJson {
+                name: &line.name,
+                choices: &line.choices,
+                quantity: line.quantity,
+                unit_cents: line.unit_cents,
+                total_cents: line.total_cents,
+            })
+            .collect(),
+        subtotal_cents: bill.subtotal(),
+        discount_cents: bill.discount(),
+        tax_rate_basis_points: bill.tax_rate(),
+        tax_cents: bill.tax(),
+        tip_cents: bill.tip(),
+        total_cents: bill.total(),
+    };
+    serde_json::to_string_pretty(&json).expect("a bill always serializes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::front_of_house::billing::BillSettings;
+    use crate::front_of_house::serving::{take_order, OrderBook, OrderItem, OrderStatus};
+
+    const MENU: &str = r#"
+        [[items]]
+        name = "Crème brûlée"
+        category = "dessert"
+        price_cents = 800
+
+        [[items]]
+        name = "ラーメン"
+        category = "main"
+        price_cents = 1400
+        station = "stove"
+
+        [[items.modifiers]]
+        name = "broth"
+        options = [{ name = "Shōyu" }, { name = "Miso" }]
+    "#;
+
+    fn served(items: Vec<OrderItem>) -> Order {
+        let mut book = OrderBook::new();
+        let id = take_order(&mut book, 4, items).unwrap();
+        let order = book.get_mut(id).unwrap();
+        for status in [
+            OrderStatus::Cooking,
+            OrderStatus::Ready,
+            OrderStatus::Served,
+        ] {
+            order.advance(status).unwrap();
+        }
+        order.clone()
+    }
+
+    #[test]
+    fn measures_columns_not_bytes() {
+        assert_eq!(text_width("Crème brûlée"), 12);
+        assert_eq!(text_width("ラーメン"), 8);
+        assert_eq!(truncate("ラーメン", 6), "ラー…");
+        assert_eq!(truncate("ラーメン", 4), "ラ…");
+        assert_eq!(columns("Crème", "$1.00", 12), "Crème  $1.00");
+        assert_eq!(centre("ラ", 5), " ラ  ");
+    }
+
+    #[test]
+    fn receipt_lines_are_all_the_same_width() {
+        let menu = Menu::from_toml(MENU).unwrap();
+        let order = served(vec![
+            OrderItem::new("ラーメン", 2).with_choice("broth", "Shōyu"),
+            OrderItem::new("Crème brûlée", 1),
+        ]);
+        let mut settings = BillSettings::with_tax(825);
+        settings.tip_cents = 500;
+        let bill = Bill::for_order(&order, &menu, &settings).unwrap();
+
+        let printed = receipt("Ferris's Diner", &order, &bill, RECEIPT_WIDTH);
+        for line in printed.lines() {
+            assert_eq!(text_width(line), RECEIPT_WIDTH, "{:?}", line);
+        }
+        let lines: Vec<_> = printed.lines().collect();
+        assert_eq!(lines[3], columns("2 x ラーメン", "$28.00", RECEIPT_WIDTH));
+        assert_eq!(lines[4].trim(), "Shōyu");
+        assert_eq!(lines[5].trim(), "@ $14.00 each");
+        assert!(printed.contains("Tax 8.25%"));
+        assert!(lines.last().unwrap().ends_with("$43.97"));
+
+        // On narrow paper long names are cut, but the prices still line up. Paper narrower than a price cuts that too.
+        for width in [16, 6, 4] {
+            for line in receipt("Ferris's Diner", &order, &bill, width).lines() {
+                assert_eq!(text_width(line), width, "{:?}", line);
+            }
+        }
+        assert_eq!(columns("a", "$100.00", 4), "$10…");
+    }
+
+    #[test]
+    fn kitchen_ticket_groups_by_station() {
+        let menu = Menu::from_toml(MENU).unwrap();
+        let order = served(vec![
+            OrderItem::new("Crème brûlée", 1),
+            OrderItem::new("ラーメン", 1).with_choice("broth", "Miso"),
+            OrderItem::new("Toast", 1),
+        ]);
+
+        assert_eq!(
+            kitchen_ticket(&order, &menu),
+            "ORDER 1  TABLE 4\n\
+             [stove]\n  1 x ラーメン\n      broth: Miso\n\
+             [pastry]\n  1 x Crème brûlée\n\
+             [not on the menu]\n  1 x Toast\n"
+        );
+    }
+
+    #[test]
+    fn json_keeps_names_and_cents() {
+        let menu = Menu::from_toml(MENU).unwrap();
+        let order = served(vec![
+            OrderItem::new("ラーメン", 1).with_choice("broth", "Miso")
+        ]);
+        let bill = Bill::for_order(&order, &menu, &BillSettings::with_tax(1000)).unwrap();
+
+        let order: serde_json::Value = serde_json::from_str(&order_json(&order)).unwrap();
+        assert_eq!(order["status"], "served");
+        assert_eq!(order["items"][0]["name"], "ラーメン");
+        assert_eq!(order["items"][0]["choices"]["broth"], "Miso");
+        assert_eq!(order["history"].as_array().unwrap().len(), 4);
+
+        let bill: serde_json::Value = serde_json::from_str(&bill_json(&bill)).unwrap();
+        assert_eq!(bill["subtotal_cents"], 1400);
+        assert_eq!(bill["tax_cents"], 140);
+        assert_eq!(bill["total_cents"], 1540);
+        assert_eq!(bill["lines"][0]["choices"][0], "Miso");
+    }
+}
@@ -0,0 +1,43 @@
+// The time of year, shared by the kitchen, which cooks with what is in season, and the garden, which sows by it.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    // Meteorological seasons for the northern hemisphere, `month` runs from 1 to 12.
+    pub fn from_month(month: u32) -> Option<Season> {
+        match month {
+            3..=5 => Some(Season::Spring),
+            6..=8 => Some(Season::Summer),
+            9..=11 => Some(Season::Autumn),
+            12 | 1 | 2 => Some(Season::Winter),
+            _ => None,
+        }
+    }
+
+    pub fn fruit(&self) -> &'static str {
+        match self {
+            Season::Spring => "strawberries",
+            Season::Summer => "peaches",
+            Season::Autumn => "apples",
+            Season::Winter => "oranges",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seasons_follow_the_month() {
+        assert_eq!(Season::from_month(7), Some(Season::Summer));
+        assert_eq!(Season::from_month(12).unwrap().fruit(), "oranges");
+        assert_eq!(Season::from_month(13), None);
+    }
+}
@@ -0,0 +1,527 @@
+// A discrete-event simulation of an evening at the restaurant. Parties arrive at random, wait for a table,
+// order, wait for the kitchen, eat and pay, all on the same simulated clock the kitchen uses.
+// The same seed always plays out the same evening, so runs with different settings can be compared.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::back_of_house::kitchen::{Kitchen, KitchenStats, Minutes};
+use crate::back_of_house::menu::{Category, Cents, Menu, MenuItem};
+use crate::back_of_house::{cook_order, order_up};
+use crate::front_of_house::billing::{self, BasisPoints, Bill, BillSettings};
+use crate::front_of_house::hosting::{self, PartyId, TableId, TableInventory, Waitlist};
+use crate::front_of_house::serving::{self, OrderBook, OrderId, OrderItem};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationConfig {
+    pub seed: u64,
+    // How long the doors are open. Parties only arrive while they are, the ones inside still finish their meal.
+    // At most MAX_HOURS, longer evenings are cut short.
+    pub hours: u32,
+    pub arrivals_per_hour: f64,
+    pub tables: Vec<u32>,
+    pub max_party_size: u32,
+    // How long a party stays on the waitlist before giving up and leaving.
+    pub patience_minutes: Minutes,
+    // From sitting down to the order reaching the kitchen.
+    pub ordering_minutes: Minutes,
+    // Average time from the food arriving to paying, each party takes between half and one and a half times this.
+    pub eating_minutes: Minutes,
+    pub tax_rate: BasisPoints,
+    pub tip_rate: BasisPoints,
+}
+
+// A week. Enough for any evening, and small enough that the minutes it lasts fit in a Minutes.
+pub const MAX_HOURS: u32 = 7 * 24;
+
+impl Default for SimulationConfig {
+    fn default() -> SimulationConfig {
+        SimulationConfig {
+            seed: 0,
+            hours: 4,
+            arrivals_per_hour: 12.0,
+            tables: vec![2, 2, 2, 4, 4, 6],
+            max_party_size: 6,
+            patience_minutes: 30,
+            ordering_minutes: 5,
+            eating_minutes: 40,
+            tax_rate: 825,
+            tip_rate: 1800,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HourReport {
+    pub hour: u32,
+    pub arrivals: usize,
+    pub seated: usize,
+    // Totals of the bills paid during the hour, tax and tip included.
+    pub revenue: Cents,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub arrivals: usize,
+    pub seated: usize,
+    pub walked_away: usize,
+    // Parties that sat down but found nothing on the menu to eat, so got up and left without a bill.
+    pub left_without_ordering: usize,
+    pub paid: usize,
+    pub revenue: Cents,
+    // Minutes from arriving to sitting down, over the parties that were seated.
+    pub average_wait_minutes: f64,
+    pub longest_wait_minutes: Minutes,
+    // Share of table time that had someone sitting at it, between opening and the last party leaving.
+    pub table_utilisation: f64,
+    pub closed_at: Minutes,
+    pub hours: Vec<HourReport>,
+    pub kitchen: KitchenStats,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} parties arrived, {} were seated, {} walked away, {} paid",
+            self.arrivals, self.seated, self.walked_away, self.paid
+        )?;
+        if self.left_without_ordering > 0 {
+            writeln!(f, "{} left without ordering", self.left_without_ordering)?;
+        }
+        writeln!(
+            f,
+            "Average wait {:.1} minutes, longest {} minutes",
+            self.average_wait_minutes, self.longest_wait_minutes
+        )?;
+        writeln!(
+            f,
+            "Tables were in use {:.1}% of the time, the last party left after {} minutes",
+            self.table_utilisation * 100.0,
+            self.closed_at
+        )?;
+        writeln!(
+            f,
+            "The kitchen cooked {} tickets, {:.1} minutes each on average",
+            self.kitchen.completed_tickets, self.kitchen.average_ticket_minutes
+        )?;
+        writeln!(f, "Hour  Arrivals  Seated     Revenue")?;
+        for hour in &self.hours {
+            writeln!(
+                f,
+                "{:>4}  {:>8}  {:>6}  {:>10}",
+                hour.hour,
+                hour.arrivals,
+                hour.seated,
+                billing::format_cents(hour.revenue)
+            )?;
+        }
+        write!(f, "Total revenue {}", billing::format_cents(self.revenue))
+    }
+}
+
+// Events are ordered by time, then by when they were scheduled, so ties play out first come first served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Event {
+    Arrival { size: u32 },
+    GiveUp { party: PartyId },
+    PlaceOrder { table: TableId, guests: u32 },
+    Leave { order: OrderId },
+}
+
+struct Simulation<'a> {
+    config: &'a SimulationConfig,
+    menu: &'a Menu,
+    rng: StdRng,
+    events: BinaryHeap<Reverse<(Minutes, u64, Event)>>,
+    scheduled: u64,
+    waitlist: Waitlist,
+    tables: TableInventory,
+    orders: OrderBook,
+    kitchen: Kitchen,
+    arrived_at: HashMap<PartyId, Minutes>,
+    seated_at: HashMap<TableId, Minutes>,
+    table_minutes: u64,
+    // When each party sat down and how long it had waited.
+    seatings: Vec<(Minutes, Minutes)>,
+    walked_away: usize,
+    left_without_ordering: usize,
+    // The last time anyone came, went or paid.
+    closed_at: Minutes,
+    bills: Vec<(Minutes, Bill)>,
+    arrivals: Vec<Minutes>,
+}
+
+impl<'a> Simulation<'a> {
+    fn new(config: &'a SimulationConfig, menu: &'a Menu) -> Simulation<'a> {
+        let mut tables = TableInventory::new();
+        for &capacity in &config.tables {
+            tables.add_table(capacity);
+        }
+
+        Simulation {
+            config,
+            menu,
+            rng: StdRng::seed_from_u64(config.seed),
+            events: BinaryHeap::new(),
+            scheduled: 0,
+            waitlist: Waitlist::new(),
+            tables,
+            orders: OrderBook::new(),
+            kitchen: Kitchen::new(),
+            arrived_at: HashMap::new(),
+            seated_at: HashMap::new(),
+            table_minutes: 0,
+            seatings: Vec::new(),
+            walked_away: 0,
+            left_without_ordering: 0,
+            closed_at: 0,
+            bills: Vec::new(),
+            arrivals: Vec::new(),
+        }
+    }
+
+    fn schedule(&mut self, time: Minutes, event: Event) {
+        self.events.push(Reverse((time, self.scheduled, event)));
+        self.scheduled += 1;
+    }
+
+    // Arrivals follow a Poisson process: the gaps between parties are exponentially distributed.
+    fn schedule_arrivals(&mut self) {
+        if self.config.arrivals_per_hour <= 0.0 {
+            return;
+        }
+        let open_for = self.config.hours.min(MAX_HOURS) * 60;
+        let mut time = 0.0;
+        loop {
+            let uniform: f64 = self.rng.gen();
+            time += -(1.0 - uniform).ln() * 60.0 / self.config.arrivals_per_hour;
+            if time >= f64::from(open_for) {
+                break;
+            }
+            let size = self.rng.gen_range(1..=self.config.max_party_size.max(1));
+            self.schedule(time as Minutes, Event::Arrival { size });
+        }
+    }
+
+    fn run(mut self) -> Report {
+        self.schedule_arrivals();
+
+        loop {
+            let next_event = self.events.peek().map(|Reverse((time, _, _))| *time);
+            let next_dish = self.kitchen.next_finish();
+            let now = match (next_event, next_dish) {
+                (None, None) => break,
+                (Some(time), None) | (None, Some(time)) => time,
+                (Some(event), Some(dish)) => event.min(dish),
+            };
+
+            for order in self.kitchen.run_until(now) {
+                self.serve(now, order);
+            }
+            if next_event == Some(now) {
+                let Reverse((_, _, event)) = self.events.pop().expect("peeked above");
+                self.handle(now, event);
+            }
+        }
+
+        self.report()
+    }
+
+    fn handle(&mut self, now: Minutes, event: Event) {
+        match event {
+            Event::Arrival { size } => {
+                self.arrivals.push(now);
+                self.closed_at = now;
+                let party = hosting::add_to_waitlist(&mut self.waitlist, "guest", size)
+                    .expect("party sizes start at one");
+                self.arrived_at.insert(party, now);
+                self.schedule(now + self.config.patience_minutes, Event::GiveUp { party });
+                self.seat_parties(now);
+            }
+            Event::GiveUp { party } => {
+                if self.waitlist.remove(party).is_some() {
+                    self.walked_away += 1;
+                    self.closed_at = now;
+                }
+            }
+            Event::PlaceOrder { table, guests } => {
+                let items = self.choose_items(guests);
+                // A menu without food, and nobody fancying a drink, leaves nothing to order.
+                let Ok(id) = serving::take_order(&mut self.orders, table, items) else {
+                    self.left_without_ordering += 1;
+                    self.closed_at = now;
+                    self.free_table(now, table);
+                    return;
+                };
+                let order = self.orders.get_mut(id).expect("just taken");
+                cook_order(&mut self.kitchen, self.menu, order)
+                    .expect("guests only order from the menu");
+            }
+            Event::Leave { order } => {
+                let order = self
+                    .orders
+                    .get_mut(order)
+                    .expect("orders are never removed");
+                let table = order.table();
+                let preview = Bill::for_order(order, self.menu, &BillSettings::with_tax(0))
+                    .expect("guests only order from the menu");
+                let mut settings = BillSettings::with_tax(self.config.tax_rate);
+                settings.tip_cents = billing::percent_of(preview.subtotal(), self.config.tip_rate);
+                let bill = serving::take_payment(order, self.menu, &settings)
+                    .expect("the order was served");
+                self.bills.push((now, bill));
+                self.closed_at = now;
+                self.free_table(now, table);
+            }
+        }
+    }
+
+    fn free_table(&mut self, now: Minutes, table: TableId) {
+        self.tables.release(table).expect("the table exists");
+        let since = self.seated_at.remove(&table).expect("the table was taken");
+        self.table_minutes += u64::from(now - since);
+        self.seat_parties(now);
+    }
+
+    // The food is on the table, the guests eat and then leave.
+    fn serve(&mut self, now: Minutes, id: OrderId) {
+        let order = self
+            .orders
+            .get_mut(id)
+            .expect("the kitchen only cooks known orders");
+        order_up(order).expect("the order was cooking");
+        serving::serve_order(order).expect("the order was ready");
+
+        let eating = self.config.eating_minutes;
+        let stay = self.rng.gen_range(eating / 2..=eating + eating / 2);
+        self.schedule(now + stay, Event::Leave { order: id });
+    }
+
+    fn seat_parties(&mut self, now: Minutes) {
+        while let Some(seating) = hosting::seat_at_table(&mut self.waitlist, &mut self.tables) {
+            let arrived = self.arrived_at[&seating.party.id];
+            self.seatings.push((now, now - arrived));
+            self.seated_at.insert(seating.table, now);
+            self.schedule(
+                now + self.config.ordering_minutes,
+                Event::PlaceOrder {
+                    table: seating.table,
+                    guests: seating.party.size,
+                },
+            );
+        }
+    }
+
+    // Every guest has something from the kitchen and maybe a drink. Required choices are picked at random.
+    fn choose_items(&mut self, guests: u32) -> Vec<OrderItem> {
+        let (drinks, food): (Vec<&MenuItem>, Vec<&MenuItem>) = self
+            .menu
+            .items()
+            .iter()
+            .partition(|item| item.category == Category::Drink);
+
+        let mut items = Vec::new();
+        for _ in 0..guests {
+            if let Some(item) = food.choose(&mut self.rng) {
+                items.push(self.with_choices(item));
+            }
+            if self.rng.gen_bool(0.5) {
+                if let Some(item) = drinks.choose(&mut self.rng) {
+                    items.push(self.with_choices(item));
+                }
+            }
+        }
+        items
+    }
+
+    fn with_choices(&mut self, item: &MenuItem) -> OrderItem {
+        let mut line = OrderItem::new(&item.name, 1);
+        for modifier in item.modifiers.iter().filter(|modifier| modifier.required) {
+            if let Some(option) = modifier.options.choose(&mut self.rng) {
+                line = line.with_choice(&modifier.name, &option.name);
+            }
+        }
+        line
+    }
+
+    fn report(self) -> Report {
+        let closed_at = self.closed_at;
+        let hour_count = self.config.hours.min(MAX_HOURS).max(closed_at.div_ceil(60));
+        let mut hours: Vec<HourReport> = (0..hour_count)
+            .map(|hour| HourReport {
+                hour,
+                arrivals: 0,
+                seated: 0,
+                revenue: 0,
+            })
+            .collect();
+        let last = hours.len().saturating_sub(1);
+        let hour_of = |time: Minutes| ((time / 60) as usize).min(last);
+
+        for &time in &self.arrivals {
+            hours[hour_of(time)].arrivals += 1;
+        }
+        for &(time, _) in &self.seatings {
+            hours[hour_of(time)].seated += 1;
+        }
+        for (time, bill) in &self.bills {
+            hours[hour_of(*time)].revenue += bill.total();
+        }
+
+        let seated = self.seatings.len();
+        let total_wait: Minutes = self.seatings.iter().map(|&(_, wait)| wait).sum();
+        let available = self.config.tables.len() as u64 * u64::from(closed_at);
+
+        Report {
+            arrivals: self.arrivals.len(),
+            seated,
+            walked_away: self.walked_away,
+            left_without_ordering: self.left_without_ordering,
+            paid: self.bills.len(),
+            revenue: self.bills.iter().map(|(_, bill)| bill.total()).sum(),
+            average_wait_minutes: if seated == 0 {
+                0.0
+            } else {
+                f64::from(total_wait) / seated as f64
+            },
+            longest_wait_minutes: self
+                .seatings
+                .iter()
+                .map(|&(_, wait)| wait)
+                .max()
+                .unwrap_or(0),
+            table_utilisation: if available == 0 {
+                0.0
+            } else {
+                self.table_minutes as f64 / available as f64
+            },
+            closed_at,
+            hours,
+            kitchen: self.kitchen.stats(),
+        }
+    }
+}
+
+// Plays out one evening with `config` and the dishes on `menu`.
+pub fn run(config: &SimulationConfig, menu: &Menu) -> Report {
+    Simulation::new(config, menu).run()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_plays_out_the_same_evening() {
+        let menu = Menu::house();
+        let config = SimulationConfig {
+            seed: 7,
+            ..SimulationConfig::default()
+        };
+
+        let first = run(&config, &menu);
+        assert_eq!(first, run(&config, &menu));
+        assert!(first.arrivals > 0);
+        assert_ne!(
+            first,
+            run(
+                &SimulationConfig {
+                    seed: 8,
+                    ..config.clone()
+                },
+                &menu
+            )
+        );
+    }
+
+    #[test]
+    fn every_party_is_accounted_for() {
+        let menu = Menu::house();
+        let config = SimulationConfig {
+            seed: 3,
+            arrivals_per_hour: 30.0,
+            ..SimulationConfig::default()
+        };
+        let report = run(&config, &menu);
+
+        assert_eq!(report.seated + report.walked_away, report.arrivals);
+        assert_eq!(report.paid, report.seated);
+        assert_eq!(report.left_without_ordering, 0);
+        assert!(report.walked_away > 0, "a busy night turns people away");
+        assert_eq!(
+            report.hours.iter().map(|hour| hour.revenue).sum::<Cents>(),
+            report.revenue
+        );
+        assert_eq!(
+            report.hours.iter().map(|hour| hour.arrivals).sum::<usize>(),
+            report.arrivals
+        );
+        assert!(report.table_utilisation > 0.0 && report.table_utilisation <= 1.0);
+        assert!(report.closed_at >= config.hours * 60 - 60);
+    }
+
+    #[test]
+    fn quiet_evening_has_no_waits() {
+        let menu = Menu::house();
+        let config = SimulationConfig {
+            arrivals_per_hour: 1.0,
+            tables: vec![6; 10],
+            ..SimulationConfig::default()
+        };
+        let report = run(&config, &menu);
+
+        assert_eq!(report.walked_away, 0);
+        assert_eq!(report.longest_wait_minutes, 0);
+
+        let closed = run(
+            &SimulationConfig {
+                arrivals_per_hour: 0.0,
+                ..config
+            },
+            &menu,
+        );
+        assert_eq!((closed.arrivals, closed.revenue), (0, 0));
+        assert_eq!(closed.hours.len(), 4);
+
+        let endless = run(
+            &SimulationConfig {
+                arrivals_per_hour: 0.0,
+                hours: u32::MAX,
+                ..SimulationConfig::default()
+            },
+            &menu,
+        );
+        assert_eq!(endless.hours.len(), MAX_HOURS as usize);
+    }
+
+    #[test]
+    fn nothing_to_eat_means_nothing_to_pay() {
+        let menu = Menu::from_toml(
+            r#"
+            [[items]]
+            name = "Lemonade"
+            category = "drink"
+            price_cents = 300
+            "#,
+        )
+        .unwrap();
+        let report = run(&SimulationConfig::default(), &menu);
+
+        assert!(report.left_without_ordering > 0);
+        assert_eq!(report.paid + report.left_without_ordering, report.seated);
+        assert_eq!(
+            run(
+                &SimulationConfig::default(),
+                &Menu::from_toml("items = []").unwrap()
+            )
+            .paid,
+            0
+        );
+    }
+}
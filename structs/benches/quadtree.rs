@@ -0,0 +1,73 @@
+// cargo bench --bench quadtree
+// Compares QuadTree against checking every rectangle, for finding all overlapping pairs and for a single range query.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use structs::quadtree::{naive_overlaps, QuadTree};
+use structs::Rectangle;
+
+const SIZES: [usize; 3] = [100, 1_000, 5_000];
+
+// Small rectangles spread over a 10000x10000 layout, like widgets on a large canvas.
+fn layout(count: usize) -> Vec<Rectangle> {
+    let mut rng = StdRng::seed_from_u64(42);
+    (0..count)
+        .map(|_| {
+            Rectangle::at(
+                rng.gen_range(0..10_000),
+                rng.gen_range(0..10_000),
+                rng.gen_range(1..100),
+                rng.gen_range(1..100),
+            )
+        })
+        .collect()
+}
+
+fn tree_of(rects: &[Rectangle]) -> QuadTree<()> {
+    let mut tree = QuadTree::new(Rectangle::square(10_000));
+    for rect in rects {
+        tree.insert(*rect, ());
+    }
+    tree
+}
+
+fn overlaps(c: &mut Criterion) {
+    let mut group = c.benchmark_group("overlaps");
+    for size in SIZES {
+        let rects = layout(size);
+        group.bench_with_input(BenchmarkId::new("naive", size), &rects, |b, rects| {
+            b.iter(|| naive_overlaps(black_box(rects)))
+        });
+        // Building the tree is part of the cost, since a scan needs no set up.
+        group.bench_with_input(BenchmarkId::new("quadtree", size), &rects, |b, rects| {
+            b.iter(|| tree_of(black_box(rects)).overlaps())
+        });
+    }
+    group.finish();
+}
+
+fn range_query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("range_query");
+    let area = Rectangle::at(4_000, 4_000, 500, 500);
+    for size in SIZES {
+        let rects = layout(size);
+        let tree = tree_of(&rects);
+        group.bench_with_input(BenchmarkId::new("naive", size), &rects, |b, rects| {
+            b.iter(|| {
+                rects
+                    .iter()
+                    .filter(|rect| rect.intersects(black_box(&area)))
+                    .count()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("quadtree", size), &tree, |b, tree| {
+            b.iter(|| tree.query(black_box(&area)).len())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, overlaps, range_query);
+criterion_main!(benches);
@@ -0,0 +1,436 @@
+// Colours as 8-bit sRGB channels plus alpha, grown out of the Color(i32, i32, i32) tuple struct in src/main.rs.
+//
+// u8 channels can not go below 0 or above 255, so a Color is always valid. Values from outside, like i32s or hex
+// strings, are checked on the way in.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorError {
+    ChannelOutOfRange { channel: &'static str, value: i64 },
+    // Hex colours have 3, 4, 6 or 8 digits after an optional '#'.
+    BadHexLength(usize),
+    BadHexDigit(char),
+    // Saturation, lightness and value go from 0 to 1, and the hue must be a number.
+    ComponentOutOfRange { component: &'static str, value: f64 },
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorError::ChannelOutOfRange { channel, value } => {
+                write!(f, "{} channel {} is outside 0 to 255", channel, value)
+            }
+            ColorError::BadHexLength(digits) => {
+                write!(f, "a hex colour has 3, 4, 6 or 8 digits, not {}", digits)
+            }
+            ColorError::BadHexDigit(c) => write!(f, "{:?} is not a hex digit", c),
+            ColorError::ComponentOutOfRange { component, value } => {
+                write!(f, "{} {} is outside 0 to 1", component, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ColorError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    // 0 is fully transparent, 255 fully opaque.
+    pub a: u8,
+}
+
+// Hue in degrees from 0 up to 360, saturation and lightness from 0 to 1. The fields are private so every Hsl has
+// gone through new, or been converted from a Color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    h: f64,
+    s: f64,
+    l: f64,
+}
+
+// Hue in degrees from 0 up to 360, saturation and value from 0 to 1, kept valid the same way as Hsl.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    h: f64,
+    s: f64,
+    v: f64,
+}
+
+// Checks a 0 to 1 component and wraps the hue into 0 up to 360.
+fn checked(h: f64, components: [(&'static str, f64); 2]) -> Result<f64, ColorError> {
+    if !h.is_finite() {
+        return Err(ColorError::ComponentOutOfRange {
+            component: "hue",
+            value: h,
+        });
+    }
+    for (component, value) in components {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(ColorError::ComponentOutOfRange { component, value });
+        }
+    }
+    Ok(h.rem_euclid(360.0))
+}
+
+impl Hsl {
+    pub fn new(h: f64, s: f64, l: f64) -> Result<Hsl, ColorError> {
+        let h = checked(h, [("saturation", s), ("lightness", l)])?;
+        Ok(Hsl { h, s, l })
+    }
+
+    pub fn hue(&self) -> f64 {
+        self.h
+    }
+
+    pub fn saturation(&self) -> f64 {
+        self.s
+    }
+
+    pub fn lightness(&self) -> f64 {
+        self.l
+    }
+}
+
+impl Hsv {
+    pub fn new(h: f64, s: f64, v: f64) -> Result<Hsv, ColorError> {
+        let h = checked(h, [("saturation", s), ("value", v)])?;
+        Ok(Hsv { h, s, v })
+    }
+
+    pub fn hue(&self) -> f64 {
+        self.h
+    }
+
+    pub fn saturation(&self) -> f64 {
+        self.s
+    }
+
+    pub fn value(&self) -> f64 {
+        self.v
+    }
+}
+
+// WCAG 2 conformance levels, for contrast between text and its background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    AA,
+    AAA,
+}
+
+impl Level {
+    // Large text is 18pt, or 14pt bold, and up.
+    pub fn minimum_contrast(self, large_text: bool) -> f64 {
+        match (self, large_text) {
+            (Level::AA, false) => 4.5,
+            (Level::AA, true) => 3.0,
+            (Level::AAA, false) => 7.0,
+            (Level::AAA, true) => 4.5,
+        }
+    }
+}
+
+fn channel(value: f64) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+// Red, green and blue from a hue, the chroma and what to add to every channel.
+fn from_hue(h: f64, chroma: f64, offset: f64) -> (u8, u8, u8) {
+    let sector = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    (
+        channel(r + offset),
+        channel(g + offset),
+        channel(b + offset),
+    )
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color::rgba(r, g, b, 255)
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color { r, g, b, a }
+    }
+
+    // For channels that come in as wider integers, like the old Color(i32, i32, i32).
+    pub fn try_rgb(r: i64, g: i64, b: i64) -> Result<Color, ColorError> {
+        let check = |channel, value: i64| {
+            u8::try_from(value).map_err(|_| ColorError::ChannelOutOfRange { channel, value })
+        };
+        Ok(Color::rgb(
+            check("red", r)?,
+            check("green", g)?,
+            check("blue", b)?,
+        ))
+    }
+
+    pub fn with_alpha(self, a: u8) -> Color {
+        Color { a, ..self }
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.a == 255
+    }
+
+    // "#ff8800", with the alpha as two more digits unless the colour is opaque.
+    pub fn to_hex(&self) -> String {
+        let mut hex = format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b);
+        if !self.is_opaque() {
+            hex.push_str(&format!("{:02x}", self.a));
+        }
+        hex
+    }
+
+    // Channels as fractions of 255.
+    fn fractions(&self) -> (f64, f64, f64) {
+        let fraction = |c: u8| f64::from(c) / 255.0;
+        (fraction(self.r), fraction(self.g), fraction(self.b))
+    }
+
+    // The hue in degrees, the largest channel and the chroma, which HSL and HSV share.
+    fn hue(&self) -> (f64, f64, f64) {
+        let (r, g, b) = self.fractions();
+        let max = r.max(g).max(b);
+        let chroma = max - r.min(g).min(b);
+        let h = if chroma == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / chroma + 2.0)
+        } else {
+            60.0 * ((r - g) / chroma + 4.0)
+        };
+        (h, max, chroma)
+    }
+
+    pub fn to_hsl(&self) -> Hsl {
+        let (h, max, chroma) = self.hue();
+        let l = max - chroma / 2.0;
+        let s = if chroma == 0.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        Hsl { h, s, l }
+    }
+
+    pub fn to_hsv(&self) -> Hsv {
+        let (h, v, chroma) = self.hue();
+        let s = if v == 0.0 { 0.0 } else { chroma / v };
+        Hsv { h, s, v }
+    }
+
+    // Opaque. Channels are rounded to the nearest of the 256 steps, so a round trip can move a component slightly.
+    pub fn from_hsl(hsl: Hsl) -> Color {
+        let chroma = (1.0 - (2.0 * hsl.l - 1.0).abs()) * hsl.s;
+        let (r, g, b) = from_hue(hsl.h, chroma, hsl.l - chroma / 2.0);
+        Color::rgb(r, g, b)
+    }
+
+    pub fn from_hsv(hsv: Hsv) -> Color {
+        let chroma = hsv.v * hsv.s;
+        let (r, g, b) = from_hue(hsv.h, chroma, hsv.v - chroma);
+        Color::rgb(r, g, b)
+    }
+
+    // This colour painted over `background`, the usual "source over" alpha blending.
+    pub fn over(&self, background: Color) -> Color {
+        let alpha = f64::from(self.a) / 255.0;
+        let behind = f64::from(background.a) / 255.0 * (1.0 - alpha);
+        let out = alpha + behind;
+        if out == 0.0 {
+            return Color::TRANSPARENT;
+        }
+        let mix = |front: u8, back: u8| {
+            channel((f64::from(front) * alpha + f64::from(back) * behind) / 255.0 / out)
+        };
+        Color::rgba(
+            mix(self.r, background.r),
+            mix(self.g, background.g),
+            mix(self.b, background.b),
+            channel(out),
+        )
+    }
+
+    // Brightness as the eye sees it, from 0 for black to 1 for white, as WCAG defines it. Alpha is ignored.
+    pub fn relative_luminance(&self) -> f64 {
+        let linear = |c: f64| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = self.fractions();
+        0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+    }
+
+    // From 1 for no contrast up to 21 for black on white. The order of the two colours does not matter.
+    // A see-through colour is blended over the other first, and the other is taken as opaque.
+    pub fn contrast_ratio(&self, background: Color) -> f64 {
+        let background = background.with_alpha(255);
+        let lighter = self.over(background).relative_luminance();
+        let darker = background.relative_luminance();
+        let (lighter, darker) = (lighter.max(darker), lighter.min(darker));
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
+    // Whether text in this colour can be read on `background` at the given WCAG level.
+    pub fn is_readable_on(&self, background: Color, level: Level, large_text: bool) -> bool {
+        self.contrast_ratio(background) >= level.minimum_contrast(large_text)
+    }
+}
+
+impl FromStr for Color {
+    type Err = ColorError;
+
+    // "#ff8800", "ff8800", "#f80", and with alpha "#ff880080" or "#f808". Either case.
+    fn from_str(text: &str) -> Result<Color, ColorError> {
+        let text = text.trim();
+        let digits = text.strip_prefix('#').unwrap_or(text);
+        if let Some(bad) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(ColorError::BadHexDigit(bad));
+        }
+
+        let values: Vec<u8> = match digits.len() {
+            // Short forms repeat each digit, so "f80" is "ff8800".
+            3 | 4 => digits
+                .chars()
+                .map(|c| c.to_digit(16).expect("checked above") as u8 * 17)
+                .collect(),
+            6 | 8 => (0..digits.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).expect("checked above"))
+                .collect(),
+            other => return Err(ColorError::BadHexLength(other)),
+        };
+        Ok(Color::rgba(
+            values[0],
+            values[1],
+            values[2],
+            values.get(3).copied().unwrap_or(255),
+        ))
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&self.to_hex())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_hex() {
+        let orange = Color::rgb(255, 136, 0);
+        assert_eq!("#ff8800".parse(), Ok(orange));
+        assert_eq!("FF8800".parse(), Ok(orange));
+        assert_eq!("#f80".parse(), Ok(orange));
+        assert_eq!("#f808".parse(), Ok(orange.with_alpha(0x88)));
+        assert_eq!(orange.to_string(), "#ff8800");
+        assert_eq!(orange.with_alpha(128).to_string(), "#ff880080");
+
+        assert_eq!("#ff880".parse::<Color>(), Err(ColorError::BadHexLength(5)));
+        assert_eq!(
+            "#gg8800".parse::<Color>(),
+            Err(ColorError::BadHexDigit('g'))
+        );
+        assert_eq!("".parse::<Color>(), Err(ColorError::BadHexLength(0)));
+        assert_eq!(
+            Color::try_rgb(0, -1, 256),
+            Err(ColorError::ChannelOutOfRange {
+                channel: "green",
+                value: -1
+            })
+        );
+    }
+
+    #[test]
+    fn converts_to_and_from_hsl_and_hsv() {
+        let orange = Color::rgb(255, 136, 0);
+        let hsl = orange.to_hsl();
+        assert_eq!(
+            (hsl.hue().round(), hsl.saturation(), hsl.lightness()),
+            (32.0, 1.0, 0.5)
+        );
+        assert_eq!(
+            Color::from_hsl(Hsl::new(120.0, 1.0, 0.25).unwrap()),
+            Color::rgb(0, 128, 0)
+        );
+        assert_eq!(
+            Color::from_hsv(Hsv::new(-120.0, 1.0, 1.0).unwrap()),
+            Color::rgb(0, 0, 255)
+        );
+        assert_eq!(Color::WHITE.to_hsv(), Hsv::new(0.0, 0.0, 1.0).unwrap());
+        assert!(Hsl::new(0.0, 1.5, 0.5).is_err());
+        assert!(Hsv::new(f64::NAN, 0.5, 0.5).is_err());
+
+        // Every colour survives the trip through both, as 8 bits are coarser than the f64s in between.
+        for r in (0..=255).step_by(15) {
+            for g in (0..=255).step_by(17) {
+                for b in (0..=255).step_by(51) {
+                    let color = Color::rgb(r, g, b);
+                    assert_eq!(Color::from_hsl(color.to_hsl()), color);
+                    assert_eq!(Color::from_hsv(color.to_hsv()), color);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn blends_over_a_background() {
+        let red = Color::rgb(255, 0, 0);
+        let blue = Color::rgb(0, 0, 255);
+        assert_eq!(red.over(blue), red);
+        assert_eq!(red.with_alpha(0).over(blue), blue);
+        assert_eq!(red.with_alpha(128).over(blue), Color::rgb(128, 0, 127));
+        assert_eq!(
+            Color::TRANSPARENT.over(Color::TRANSPARENT),
+            Color::TRANSPARENT
+        );
+
+        // Two half-transparent layers let a quarter of what is behind them through.
+        let layered = red.with_alpha(128).over(blue.with_alpha(128));
+        assert_eq!(layered.a, 192);
+    }
+
+    #[test]
+    fn contrast_ratios_follow_wcag() {
+        assert_eq!(Color::BLACK.contrast_ratio(Color::WHITE), 21.0);
+        assert_eq!(Color::WHITE.contrast_ratio(Color::BLACK), 21.0);
+        assert_eq!(Color::WHITE.contrast_ratio(Color::WHITE), 1.0);
+
+        // #777 on white is the classic near miss: 4.48 fails AA for body text but passes for large text.
+        let grey: Color = "#777".parse().unwrap();
+        let ratio = grey.contrast_ratio(Color::WHITE);
+        assert!((ratio - 4.48).abs() < 0.01, "{}", ratio);
+        assert!(!grey.is_readable_on(Color::WHITE, Level::AA, false));
+        assert!(grey.is_readable_on(Color::WHITE, Level::AA, true));
+        assert!(Color::BLACK.is_readable_on(Color::WHITE, Level::AAA, false));
+
+        // Transparent text is as readable as the background it shows.
+        assert_eq!(Color::BLACK.with_alpha(0).contrast_ratio(Color::WHITE), 1.0);
+    }
+}
@@ -0,0 +1,8 @@
+// The library half of the crate, so other crates can use Rectangle. src/main.rs uses it as structs::Rectangle.
+pub mod color; // Checked RGB colours, hex, HSL and HSV, blending and contrast
+pub mod packing; // Fitting many rectangles into fixed-size bins
+pub mod quadtree; // Finding rectangles by area without checking every one
+pub mod rectangle;
+pub mod user; // Accounts with checked usernames and emails, kept in a UserStore
+
+pub use rectangle::Rectangle;
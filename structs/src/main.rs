@@ -1,30 +1,14 @@
-#[derive(Debug)] // This is a derive annotation, which will allow us to print the struct using the debug formatter.
-struct Rectangle {
-    width: u32,
-    height: u32,
-}
-
-// Methods are similar to functions, but they are defined within the context of a struct. They are defined using the impl keyword, followed by the struct name, and then the method name.
-// Methods. Below we will an area method for the Rectangle struct. This would be better than a function that takes a Rectangle as an argument.
-
-impl Rectangle {
-    fn area(&self) -> u32 {
-        return self.width * self.height;
-    }
+use std::time::SystemTime;
 
-    fn can_hold(&self, other: &Rectangle) -> bool {
-        return self.width > other.width && self.height > other.height;
-    }
+use structs::color::Color;
+use structs::user::{UserBuilder, UserPatch, UserStore};
+use structs::Rectangle; // Defined in src/rectangle.rs, shared with tests-in-rust-adder
+// Rectangle is #[derive(Debug)]. This is a derive annotation, which will allow us to print the struct using the debug formatter.
 
-    fn square(size: u32) -> Self{
-        return Self {
-            width: size,
-            height: size,
-        };
-    } // To call this method, we would use Rectangle::square(3), instead of rect1.square(3), because this is an associated function, not a method.
-}
-
-// All functions defined within impl blocks are associated functions, because they are associated with the struct. 
+// Methods are similar to functions, but they are defined within the context of a struct. They are defined using the impl keyword, followed by the struct name, and then the method name.
+// Methods. Rectangle has an area method. This would be better than a function that takes a Rectangle as an argument, like area1 at the bottom of this file.
+// To call Rectangle::square, we would use Rectangle::square(3), instead of rect1.square(3), because this is an associated function, not a method.
+// All functions defined within impl blocks are associated functions, because they are associated with the struct.
 
 // In C and C++, -> Operator is used to access the members of a structure using a pointer. In Rust, this is automatically done when using methods on a struct. 
 
@@ -78,19 +62,40 @@ fn main() {
 
     println!("User4: {}", user4.email);
 
+    // build_user takes any strings at all. UserStore, in src/user.rs, checks them and keeps usernames and emails unique.
+    let mut users = UserStore::new();
+    if let Err(error) = users.create("user 5", "user5@example", SystemTime::now()) {
+        println!("User5: {}", error);
+    }
+    let user5 = UserBuilder::new()
+        .username("user5")
+        .email("user5@example.com")
+        .created_at(SystemTime::now())
+        .build()
+        .and_then(|user| users.add(user).map(|user| user.id()));
+    if let Ok(id) = user5 {
+        let patch = UserPatch::new().email("another1@example.com");
+        for change in users.apply(id, &patch).unwrap() {
+            println!("User5 {}", change);
+        }
+    }
+
     // let user5 = User {
     //     email: String::from("another1@example.com"),
     //     ..user1
     // }; // .. syntax, this will fill the remaining fields with the values from user1
     // There is a limitation tho in this condition, you wont be able tu use the user1 fields as it uses String type, you can only use the fields that are not moved.
+    // structs::user gets around this with a UserPatch: it names only the fields that change and moves nothing out of user1.
 
     // Tuple Structs
     
-    struct Color(i32, i32, i32); // Used when regular structs are too verbose
-    struct Point(i32, i32, i32);
+    struct Point(i32, i32, i32); // Used when regular structs are too verbose
 
-    let black = Color(0, 0, 0);
+    // Color(i32, i32, i32) used to be a tuple struct here too, but it let channels go below 0 and above 255.
+    // The checked version lives in src/color.rs.
+    let black = Color::BLACK;
     let origin = Point(0, 0, 0);
+    println!("Black is {}, {:.0}:1 against white", black, black.contrast_ratio(Color::WHITE));
 
     // Unit like structs
     struct AlwaysEqual; // Used when you dont need any data to be associated with the struct,
@@ -102,10 +107,7 @@ fn main() {
     // Structs can hold references, but they need to be annotated with lifetimes, which we will see later.
     // Structs can also hold owned types, like String, which will be valid as long as the struct is valid.
 
-    let rect1 = Rectangle {
-        width: 30,
-        height: 50,
-    };
+    let rect1 = Rectangle::new(30, 50);
 
     println!("rect1 is {rect1:?}"); // ;? tells the compiler to use the debug formatter to print the struct.It uses an output format known as Debug, which is designed for use in debugging scenarios.
     println!("The area of the rectangle is: {}", area1(&rect1));
@@ -117,11 +119,12 @@ fn main() {
     let rect2 = Rectangle {
         width: dbg!(30 * scale), // here dbg! will print the value of 30 * scale and return the value of 30 * scale
         height: 50,
+        ..Rectangle::default()
     };
 
     dbg!(&rect2); // We do not want the !dbg to take ownership of the struct, so we pass a reference to it. This will print the struct using the debug formatter.
 }
 
-fn area1(rectangle: &Rectangle) -> u32 {
-    return rectangle.width * rectangle.height;
+fn area1(rectangle: &Rectangle) -> u64 {
+    return u64::from(rectangle.width) * u64::from(rectangle.height);
 }
\ No newline at end of file
@@ -0,0 +1,471 @@
+// Packing rectangles into as few fixed-size bins as possible, like cutting parts out of sheets of plywood.
+//
+// Every heuristic places the largest rectangles first and puts each one in the first bin it fits in, opening a new bin
+// when none has room. They differ in how they track the space left in a bin:
+// - Shelf stacks rows ("shelves") from the top down and fills each row left to right. Fast, but wastes the space above
+//   short rectangles.
+// - Guillotine keeps a list of free rectangles and cuts the one it uses into two, the way a guillotine cuts paper.
+// - MaxRects keeps every maximal free rectangle, overlapping each other, so no free space is ever lost to a cut.
+//   Slower, but usually the tightest.
+
+use std::fmt;
+
+use crate::Rectangle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Heuristic {
+    Shelf,
+    Guillotine,
+    #[default]
+    MaxRects,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PackOptions {
+    pub heuristic: Heuristic,
+    // Whether rectangles may be turned 90° to fit better.
+    pub allow_rotation: bool,
+}
+
+// Where one of the input rectangles ended up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    // Index into the slice given to pack.
+    pub index: usize,
+    pub bin: usize,
+    // Position inside the bin, with the bin's top left corner at (0, 0). Width and height are swapped when rotated.
+    pub rect: Rectangle,
+    pub rotated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packing {
+    bin: Rectangle,
+    bins: usize,
+    placements: Vec<Placement>,
+    unplaced: Vec<usize>,
+}
+
+impl Packing {
+    // The size of every bin.
+    pub fn bin(&self) -> Rectangle {
+        self.bin
+    }
+
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    // In the order the input was given.
+    pub fn placements(&self) -> &[Placement] {
+        &self.placements
+    }
+
+    pub fn in_bin(&self, bin: usize) -> impl Iterator<Item = &Placement> {
+        self.placements.iter().filter(move |p| p.bin == bin)
+    }
+
+    // Indices of the rectangles that are too big for an empty bin, even turned.
+    pub fn unplaced(&self) -> &[usize] {
+        &self.unplaced
+    }
+
+    pub fn used_area(&self) -> u64 {
+        self.placements.iter().map(|p| p.rect.area()).sum()
+    }
+
+    // Area of the bins used that no rectangle covers.
+    pub fn wasted_area(&self) -> u64 {
+        self.bins as u64 * self.bin.area() - self.used_area()
+    }
+
+    // Share of the bins' area that is used, between 0 and 1.
+    pub fn utilisation(&self) -> f64 {
+        if self.bins == 0 {
+            return 0.0;
+        }
+        self.used_area() as f64 / (self.bins as u64 * self.bin.area()) as f64
+    }
+}
+
+impl fmt::Display for Packing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} rectangles in {} bins of {}x{}, {} wasted ({:.1}% used)",
+            self.placements.len(),
+            self.bins,
+            self.bin.width,
+            self.bin.height,
+            self.wasted_area(),
+            self.utilisation() * 100.0
+        )?;
+        if !self.unplaced.is_empty() {
+            write!(f, ", {} too big to place", self.unplaced.len())?;
+        }
+        Ok(())
+    }
+}
+
+// One row of a shelf-packed bin.
+#[derive(Debug)]
+struct Shelf {
+    top: i64,
+    height: i64,
+    used: i64,
+}
+
+#[derive(Debug)]
+enum Space {
+    Shelves(Vec<Shelf>),
+    // Free rectangles for Guillotine, which never overlap, and for MaxRects, which do.
+    Free(Vec<Rectangle>),
+}
+
+// Where a rectangle would go in a bin, and how good a fit that is. Lower scores are better.
+#[derive(Debug)]
+struct Candidate {
+    rect: Rectangle,
+    rotated: bool,
+    score: (i64, i64),
+    // The shelf or free rectangle used. A shelf index one past the end opens a new shelf.
+    slot: usize,
+}
+
+#[derive(Debug)]
+struct Bin {
+    size: Rectangle,
+    space: Space,
+}
+
+impl Bin {
+    fn new(size: Rectangle, heuristic: Heuristic) -> Bin {
+        let space = match heuristic {
+            Heuristic::Shelf => Space::Shelves(Vec::new()),
+            Heuristic::Guillotine | Heuristic::MaxRects => Space::Free(vec![size]),
+        };
+        Bin { size, space }
+    }
+
+    fn find(&self, item: &Rectangle, options: &PackOptions) -> Option<Candidate> {
+        let mut orientations = vec![(i64::from(item.width), i64::from(item.height), false)];
+        if options.allow_rotation && item.width != item.height {
+            orientations.push((i64::from(item.height), i64::from(item.width), true));
+        }
+
+        let mut best: Option<Candidate> = None;
+        let mut consider = |x: i64, y: i64, (w, h, rotated): (i64, i64, bool), score, slot| {
+            if best.as_ref().is_none_or(|b| score < b.score) {
+                let rect = Rectangle::from_edges(x, y, x + w, y + h).expect("inside the bin");
+                best = Some(Candidate {
+                    rect,
+                    rotated,
+                    score,
+                    slot,
+                });
+            }
+        };
+
+        match &self.space {
+            Space::Shelves(shelves) => {
+                // Prefer an open shelf, wasting as little of its height as possible.
+                for (slot, shelf) in shelves.iter().enumerate() {
+                    for &(w, h, rotated) in &orientations {
+                        if h <= shelf.height && shelf.used + w <= self.size.right() {
+                            consider(
+                                shelf.used,
+                                shelf.top,
+                                (w, h, rotated),
+                                (0, shelf.height - h),
+                                slot,
+                            );
+                        }
+                    }
+                }
+                // Otherwise start a new shelf, as flat as possible to leave room for more.
+                let top = shelves.last().map_or(0, |shelf| shelf.top + shelf.height);
+                for &(w, h, rotated) in &orientations {
+                    if w <= self.size.right() && top + h <= self.size.bottom() {
+                        consider(0, top, (w, h, rotated), (1, h), shelves.len());
+                    }
+                }
+            }
+            Space::Free(free) => {
+                for (slot, space) in free.iter().enumerate() {
+                    for &(w, h, rotated) in &orientations {
+                        let (spare_w, spare_h) =
+                            (i64::from(space.width) - w, i64::from(space.height) - h);
+                        if spare_w < 0 || spare_h < 0 {
+                            continue;
+                        }
+                        let score = if options.heuristic == Heuristic::Guillotine {
+                            // Best area fit: the free rectangle that is left with the least over.
+                            (space.area() as i64 - w * h, spare_w.min(spare_h))
+                        } else {
+                            // Best short side fit: the free rectangle it fills most snugly in one direction.
+                            (spare_w.min(spare_h), spare_w.max(spare_h))
+                        };
+                        consider(space.left(), space.top(), (w, h, rotated), score, slot);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    fn commit(&mut self, candidate: &Candidate, heuristic: Heuristic) {
+        let placed = candidate.rect;
+        match &mut self.space {
+            Space::Shelves(shelves) => {
+                if candidate.slot == shelves.len() {
+                    shelves.push(Shelf {
+                        top: placed.top(),
+                        height: i64::from(placed.height),
+                        used: 0,
+                    });
+                }
+                shelves[candidate.slot].used = placed.right();
+            }
+            Space::Free(free) if heuristic == Heuristic::Guillotine => {
+                let space = free.swap_remove(candidate.slot);
+                free.extend(guillotine_cut(&space, &placed));
+            }
+            Space::Free(free) => {
+                let mut split = Vec::new();
+                for space in free.drain(..) {
+                    if space.intersects(&placed) {
+                        split.extend(maximal_rest(&space, &placed));
+                    } else {
+                        split.push(space);
+                    }
+                }
+                *free = prune(split);
+            }
+        }
+    }
+}
+
+// What is left of `space` once `placed` is cut from its top left corner: two rectangles, split along the shorter leftover.
+fn guillotine_cut(space: &Rectangle, placed: &Rectangle) -> Vec<Rectangle> {
+    let spare_w = space.right() - placed.right();
+    let spare_h = space.bottom() - placed.bottom();
+    let (right, below) = if spare_w < spare_h {
+        // Cut across: the piece below gets the full width.
+        (
+            Rectangle::from_edges(placed.right(), space.top(), space.right(), placed.bottom()),
+            Rectangle::from_edges(space.left(), placed.bottom(), space.right(), space.bottom()),
+        )
+    } else {
+        // Cut down: the piece to the right gets the full height.
+        (
+            Rectangle::from_edges(placed.right(), space.top(), space.right(), space.bottom()),
+            Rectangle::from_edges(
+                space.left(),
+                placed.bottom(),
+                placed.right(),
+                space.bottom(),
+            ),
+        )
+    };
+    [right, below]
+        .into_iter()
+        .flatten()
+        .filter(|rect| !rect.is_empty())
+        .collect()
+}
+
+// The up to four maximal rectangles of `space` around `placed`, one for each side. They overlap each other.
+fn maximal_rest(space: &Rectangle, placed: &Rectangle) -> Vec<Rectangle> {
+    [
+        Rectangle::from_edges(space.left(), space.top(), placed.left(), space.bottom()),
+        Rectangle::from_edges(placed.right(), space.top(), space.right(), space.bottom()),
+        Rectangle::from_edges(space.left(), space.top(), space.right(), placed.top()),
+        Rectangle::from_edges(space.left(), placed.bottom(), space.right(), space.bottom()),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|rect| !rect.is_empty())
+    .collect()
+}
+
+// Drops free rectangles that lie inside another one, keeping one of any duplicates.
+fn prune(free: Vec<Rectangle>) -> Vec<Rectangle> {
+    let mut kept: Vec<Rectangle> = Vec::with_capacity(free.len());
+    for (i, rect) in free.iter().enumerate() {
+        let covered = free
+            .iter()
+            .enumerate()
+            .any(|(j, other)| i != j && other.contains(rect) && (other != rect || j < i));
+        if !covered {
+            kept.push(*rect);
+        }
+    }
+    kept
+}
+
+// Places `items` into as few bins of `bin`'s size as the heuristic manages. The position of `bin` is ignored.
+//
+// Panics if the bin is wider or taller than i32::MAX, since positions inside it would not fit in a Rectangle.
+pub fn pack(bin: Rectangle, items: &[Rectangle], options: PackOptions) -> Packing {
+    assert!(
+        i32::try_from(bin.width).is_ok() && i32::try_from(bin.height).is_ok(),
+        "bin {}x{} is too big to hold positions",
+        bin.width,
+        bin.height
+    );
+    let size = Rectangle::new(bin.width, bin.height);
+
+    // Biggest first: the longest side, then the area. Small rectangles fill the gaps the big ones leave.
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by_key(|&i| {
+        let item = &items[i];
+        std::cmp::Reverse((item.width.max(item.height), item.area()))
+    });
+
+    let mut bins: Vec<Bin> = Vec::new();
+    let mut placements = Vec::with_capacity(items.len());
+    let mut unplaced = Vec::new();
+    for index in order {
+        let item = &items[index];
+        let fits_empty = size.fits(item) || (options.allow_rotation && size.fits(&item.rotated()));
+        if item.is_empty() || !fits_empty {
+            unplaced.push(index);
+            continue;
+        }
+
+        let found = bins
+            .iter()
+            .enumerate()
+            .find_map(|(i, bin)| bin.find(item, &options).map(|candidate| (i, candidate)));
+        let (bin_index, candidate) = match found {
+            Some(found) => found,
+            None => {
+                let bin = Bin::new(size, options.heuristic);
+                let candidate = bin.find(item, &options).expect("fits in an empty bin");
+                bins.push(bin);
+                (bins.len() - 1, candidate)
+            }
+        };
+        bins[bin_index].commit(&candidate, options.heuristic);
+        placements.push(Placement {
+            index,
+            bin: bin_index,
+            rect: candidate.rect,
+            rotated: candidate.rotated,
+        });
+    }
+
+    placements.sort_by_key(|p| p.index);
+    unplaced.sort_unstable();
+    Packing {
+        bin: size,
+        bins: bins.len(),
+        placements,
+        unplaced,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEURISTICS: [Heuristic; 3] =
+        [Heuristic::Shelf, Heuristic::Guillotine, Heuristic::MaxRects];
+
+    fn check(packing: &Packing) {
+        for (i, a) in packing.placements().iter().enumerate() {
+            assert!(
+                packing.bin().contains(&a.rect),
+                "{} is outside the bin",
+                a.rect
+            );
+            for b in &packing.placements()[i + 1..] {
+                assert!(
+                    a.bin != b.bin || !a.rect.intersects(&b.rect),
+                    "{} overlaps {}",
+                    a.rect,
+                    b.rect
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn packs_without_overlap() {
+        let items: Vec<Rectangle> = (1..=20)
+            .map(|i| Rectangle::new(i % 7 + 2, i % 5 + 3))
+            .collect();
+        for heuristic in HEURISTICS {
+            for allow_rotation in [false, true] {
+                let options = PackOptions {
+                    heuristic,
+                    allow_rotation,
+                };
+                let packing = pack(Rectangle::square(16), &items, options);
+                check(&packing);
+                assert_eq!(packing.placements().len(), items.len(), "{:?}", options);
+                let area: u64 = items.iter().map(|r| r.area()).sum();
+                assert_eq!(packing.used_area(), area);
+                assert_eq!(packing.wasted_area(), packing.bins() as u64 * 256 - area);
+            }
+        }
+    }
+
+    #[test]
+    fn perfect_fits_leave_no_waste() {
+        let quarters = [Rectangle::square(5); 4];
+        for heuristic in HEURISTICS {
+            let options = PackOptions {
+                heuristic,
+                allow_rotation: false,
+            };
+            let packing = pack(Rectangle::square(10), &quarters, options);
+            assert_eq!(packing.bins(), 1, "{:?}", heuristic);
+            assert_eq!(packing.wasted_area(), 0);
+        }
+
+        // Five quarters need a second bin.
+        let packing = pack(
+            Rectangle::square(10),
+            &[Rectangle::square(5); 5],
+            PackOptions::default(),
+        );
+        assert_eq!(packing.bins(), 2);
+        assert_eq!(packing.in_bin(1).count(), 1);
+        assert_eq!(packing.wasted_area(), 75);
+        assert_eq!(
+            packing.to_string(),
+            "5 rectangles in 2 bins of 10x10, 75 wasted (62.5% used)"
+        );
+    }
+
+    #[test]
+    fn rotation_lets_tall_rectangles_into_wide_bins() {
+        let bin = Rectangle::new(10, 3);
+        let tall = [Rectangle::new(2, 8)];
+        for heuristic in HEURISTICS {
+            let upright = pack(
+                bin,
+                &tall,
+                PackOptions {
+                    heuristic,
+                    allow_rotation: false,
+                },
+            );
+            assert_eq!(upright.unplaced(), &[0]);
+            assert_eq!(upright.bins(), 0);
+
+            let turned = pack(
+                bin,
+                &tall,
+                PackOptions {
+                    heuristic,
+                    allow_rotation: true,
+                },
+            );
+            let placement = turned.placements()[0];
+            assert!(placement.rotated);
+            assert_eq!(placement.rect, Rectangle::at(0, 0, 8, 2));
+        }
+    }
+}
@@ -0,0 +1,363 @@
+// A spatial index over positioned rectangles, so finding the ones in an area does not mean looking at all of them.
+//
+// The tree covers a fixed area and splits any part of it holding too many rectangles into four quarters. A rectangle
+// lives in the smallest quarter that contains it whole, so one lying across a split stays in the larger node above.
+// Rectangles partly or wholly outside the tree's area are kept at the root: they are still found, just not quickly.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::Rectangle;
+
+pub type ItemId = usize;
+
+// How many rectangles a node holds before it splits, unless set with QuadTree::with_capacity.
+pub const DEFAULT_CAPACITY: usize = 8;
+// Nodes this deep never split, so many copies of the same rectangle can not recurse forever.
+const MAX_DEPTH: u32 = 16;
+
+#[derive(Debug)]
+struct Node {
+    bounds: Rectangle,
+    depth: u32,
+    items: Vec<(ItemId, Rectangle)>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+impl Node {
+    fn new(bounds: Rectangle, depth: u32) -> Node {
+        Node {
+            bounds,
+            depth,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    // The child `rect` belongs in, if it fits in one whole.
+    fn child_for(&self, rect: &Rectangle) -> Option<usize> {
+        let children = self.children.as_ref()?;
+        children
+            .iter()
+            .position(|child| child.bounds.contains(rect))
+    }
+
+    fn insert(&mut self, id: ItemId, rect: Rectangle, capacity: usize) {
+        if let Some(i) = self.child_for(&rect) {
+            let children = self.children.as_mut().expect("child_for found one");
+            children[i].insert(id, rect, capacity);
+            return;
+        }
+        self.items.push((id, rect));
+        if self.children.is_none() && self.items.len() > capacity {
+            self.split(capacity);
+        }
+    }
+
+    fn split(&mut self, capacity: usize) {
+        let b = self.bounds;
+        if self.depth >= MAX_DEPTH || b.width < 2 || b.height < 2 {
+            return;
+        }
+        let (mid_x, mid_y) = (
+            b.left() + i64::from(b.width / 2),
+            b.top() + i64::from(b.height / 2),
+        );
+        let depth = self.depth + 1;
+        let quarter = |left, top, right, bottom| {
+            Rectangle::from_edges(left, top, right, bottom).map(|bounds| Node::new(bounds, depth))
+        };
+        // Bounds reaching past i32::MAX have quarters whose corner can not be stored, those nodes stay unsplit.
+        let (Some(top_left), Some(top_right), Some(bottom_left), Some(bottom_right)) = (
+            quarter(b.left(), b.top(), mid_x, mid_y),
+            quarter(mid_x, b.top(), b.right(), mid_y),
+            quarter(b.left(), mid_y, mid_x, b.bottom()),
+            quarter(mid_x, mid_y, b.right(), b.bottom()),
+        ) else {
+            return;
+        };
+        self.children = Some(Box::new([top_left, top_right, bottom_left, bottom_right]));
+
+        for (id, rect) in std::mem::take(&mut self.items) {
+            self.insert(id, rect, capacity);
+        }
+    }
+
+    // Follows the same path insert took. Children left empty are merged back into this node.
+    fn remove(&mut self, id: ItemId, rect: &Rectangle) -> bool {
+        let removed = match self.child_for(rect) {
+            Some(i) => self.children.as_mut().expect("child_for found one")[i].remove(id, rect),
+            None => match self.items.iter().position(|&(item, _)| item == id) {
+                Some(at) => {
+                    self.items.swap_remove(at);
+                    true
+                }
+                None => false,
+            },
+        };
+
+        let empty_leaves = self.children.as_ref().is_some_and(|children| {
+            children
+                .iter()
+                .all(|child| child.children.is_none() && child.items.is_empty())
+        });
+        if empty_leaves {
+            self.children = None;
+        }
+        removed
+    }
+
+    fn query(&self, area: &Rectangle, found: &mut Vec<ItemId>) {
+        found.extend(
+            self.items
+                .iter()
+                .filter(|(_, rect)| rect.intersects(area))
+                .map(|&(id, _)| id),
+        );
+        if let Some(children) = &self.children {
+            for child in children.iter() {
+                if child.bounds.intersects(area) {
+                    child.query(area, found);
+                }
+            }
+        }
+    }
+}
+
+// Squared distance from the point to the nearest part of `rect`, 0 if the point is inside.
+// u128 because the distance across the whole i32 plane squared does not fit in a u64.
+fn distance_squared(rect: &Rectangle, x: i64, y: i64) -> u128 {
+    let dx = (rect.left() - x)
+        .max(x - rect.right() + 1)
+        .max(0)
+        .unsigned_abs();
+    let dy = (rect.top() - y)
+        .max(y - rect.bottom() + 1)
+        .max(0)
+        .unsigned_abs();
+    u128::from(dx) * u128::from(dx) + u128::from(dy) * u128::from(dy)
+}
+
+#[derive(Debug)]
+pub struct QuadTree<T> {
+    root: Node,
+    capacity: usize,
+    entries: HashMap<ItemId, (Rectangle, T)>,
+    next_id: ItemId,
+}
+
+impl<T> QuadTree<T> {
+    pub fn new(bounds: Rectangle) -> QuadTree<T> {
+        QuadTree::with_capacity(bounds, DEFAULT_CAPACITY)
+    }
+
+    // Panics if `capacity` is 0.
+    pub fn with_capacity(bounds: Rectangle, capacity: usize) -> QuadTree<T> {
+        assert!(capacity > 0, "a node must hold at least one rectangle");
+        QuadTree {
+            root: Node::new(bounds, 0),
+            capacity,
+            entries: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn bounds(&self) -> Rectangle {
+        self.root.bounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Ids are handed out in order from 0 and never reused.
+    pub fn insert(&mut self, rect: Rectangle, value: T) -> ItemId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.root.insert(id, rect, self.capacity);
+        self.entries.insert(id, (rect, value));
+        id
+    }
+
+    pub fn remove(&mut self, id: ItemId) -> Option<(Rectangle, T)> {
+        let (rect, value) = self.entries.remove(&id)?;
+        let removed = self.root.remove(id, &rect);
+        debug_assert!(removed, "item {} was not where it was put", id);
+        Some((rect, value))
+    }
+
+    pub fn get(&self, id: ItemId) -> Option<(&Rectangle, &T)> {
+        self.entries.get(&id).map(|(rect, value)| (rect, value))
+    }
+
+    // Every rectangle overlapping `area`, by id. Rectangles that only touch its edge do not count.
+    pub fn query(&self, area: &Rectangle) -> Vec<ItemId> {
+        let mut found = Vec::new();
+        self.root.query(area, &mut found);
+        found.sort_unstable();
+        found
+    }
+
+    // Every pair of overlapping rectangles, smaller id first, in order.
+    pub fn overlaps(&self) -> Vec<(ItemId, ItemId)> {
+        let mut pairs = Vec::new();
+        for (&id, (rect, _)) in &self.entries {
+            pairs.extend(
+                self.query(rect)
+                    .into_iter()
+                    .filter(|&other| other > id)
+                    .map(|other| (id, other)),
+            );
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    // The rectangle closest to the point, 0 away if the point is inside it. Ties go to the smaller id.
+    pub fn nearest(&self, x: i32, y: i32) -> Option<ItemId> {
+        let (x, y) = (i64::from(x), i64::from(y));
+        let mut best: Option<(u128, ItemId)> = None;
+
+        // Nodes closest to the point first. The root goes in at 0 because it also holds what lies outside its bounds.
+        let mut nodes = vec![&self.root];
+        let mut queue = BinaryHeap::from([Reverse((0, 0))]);
+        while let Some(Reverse((distance, index))) = queue.pop() {
+            if best.is_some_and(|(closest, _)| distance > closest) {
+                break;
+            }
+            let node = nodes[index];
+            for &(id, rect) in &node.items {
+                let candidate = (distance_squared(&rect, x, y), id);
+                if best.is_none_or(|closest| candidate < closest) {
+                    best = Some(candidate);
+                }
+            }
+            if let Some(children) = &node.children {
+                for child in children.iter() {
+                    queue.push(Reverse((
+                        distance_squared(&child.bounds, x, y),
+                        nodes.len(),
+                    )));
+                    nodes.push(child);
+                }
+            }
+        }
+        best.map(|(_, id)| id)
+    }
+}
+
+// Every pair of overlapping rectangles by index, checking each pair in turn. What QuadTree::overlaps replaces.
+pub fn naive_overlaps(rects: &[Rectangle]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, a) in rects.iter().enumerate() {
+        for (j, b) in rects.iter().enumerate().skip(i + 1) {
+            if a.intersects(b) {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn scatter(count: usize, seed: u64) -> Vec<Rectangle> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..count)
+            .map(|_| {
+                Rectangle::at(
+                    rng.gen_range(-50..1000),
+                    rng.gen_range(-50..1000),
+                    rng.gen_range(0..80),
+                    rng.gen_range(1..80),
+                )
+            })
+            .collect()
+    }
+
+    fn tree_of(rects: &[Rectangle]) -> QuadTree<()> {
+        let mut tree = QuadTree::with_capacity(Rectangle::square(1000), 4);
+        for rect in rects {
+            tree.insert(*rect, ());
+        }
+        tree
+    }
+
+    #[test]
+    fn finds_the_same_as_a_scan() {
+        let rects = scatter(500, 7);
+        let tree = tree_of(&rects);
+        assert_eq!(tree.overlaps(), naive_overlaps(&rects));
+
+        for area in scatter(50, 8) {
+            let scanned: Vec<ItemId> = (0..rects.len())
+                .filter(|&i| rects[i].intersects(&area))
+                .collect();
+            assert_eq!(tree.query(&area), scanned, "{}", area);
+        }
+    }
+
+    #[test]
+    fn removed_rectangles_are_gone() {
+        let rects = scatter(200, 3);
+        let mut tree = tree_of(&rects);
+        for id in (0..rects.len()).step_by(2) {
+            assert_eq!(tree.remove(id), Some((rects[id], ())));
+        }
+        assert_eq!(tree.remove(0), None);
+        assert_eq!(tree.len(), 100);
+        assert!(tree.query(&tree.bounds()).iter().all(|id| id % 2 == 1));
+
+        for id in (1..rects.len()).step_by(2) {
+            tree.remove(id);
+        }
+        assert!(tree.is_empty());
+        assert!(tree.root.children.is_none());
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let rects = scatter(300, 11);
+        let tree = tree_of(&rects);
+        let mut rng = StdRng::seed_from_u64(12);
+        for _ in 0..100 {
+            let (x, y) = (rng.gen_range(-200..1200), rng.gen_range(-200..1200));
+            let expected = (0..rects.len())
+                .min_by_key(|&i| (distance_squared(&rects[i], x.into(), y.into()), i));
+            assert_eq!(tree.nearest(x, y), expected, "({}, {})", x, y);
+        }
+        assert_eq!(
+            QuadTree::<()>::new(Rectangle::square(10)).nearest(0, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn rectangles_outside_the_bounds_are_still_found() {
+        let mut tree = QuadTree::new(Rectangle::square(100));
+        let far = tree.insert(Rectangle::at(5000, 5000, 10, 10), "far");
+        let near = tree.insert(Rectangle::at(10, 10, 10, 10), "near");
+        assert_eq!(tree.query(&Rectangle::at(4990, 4990, 20, 20)), vec![far]);
+        assert_eq!(tree.nearest(6000, 6000), Some(far));
+        assert_eq!(tree.nearest(0, 0), Some(near));
+        assert_eq!(tree.get(far).map(|(_, name)| *name), Some("far"));
+    }
+
+    #[test]
+    fn bounds_past_the_edge_of_the_plane_do_not_split() {
+        let mut tree = QuadTree::new(Rectangle::at(i32::MAX - 10, 0, u32::MAX, 100));
+        for i in 0..10 {
+            tree.insert(Rectangle::at(i32::MAX - 10 + i, 0, 1, 1), ());
+        }
+        assert_eq!(tree.len(), 10);
+        assert_eq!(tree.query(&Rectangle::at(i32::MAX - 10, 0, 5, 1)).len(), 5);
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt;
+
+// A rectangle with a position. x grows to the right and y grows downwards, like on a screen, and (x, y) is the top left corner.
+// The rectangle covers x..x + width and y..y + height: the left and top edges are inside it, the right and bottom edges are not.
+// So two rectangles that only touch along an edge do not overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rectangle {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rectangle {
+    // A rectangle at the origin, for when only the size matters.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::at(0, 0, width, height)
+    }
+
+    pub fn at(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn square(size: u32) -> Self {
+        Self::new(size, size)
+    }
+
+    // The edges are i64 because x + width does not always fit in an i32.
+    pub fn left(&self) -> i64 {
+        i64::from(self.x)
+    }
+
+    pub fn top(&self) -> i64 {
+        i64::from(self.y)
+    }
+
+    pub fn right(&self) -> i64 {
+        self.left() + i64::from(self.width)
+    }
+
+    pub fn bottom(&self) -> i64 {
+        self.top() + i64::from(self.height)
+    }
+
+    // The rectangle between the given edges, or None if the edges are the wrong way round or it cannot be stored as a Rectangle.
+    pub fn from_edges(left: i64, top: i64, right: i64, bottom: i64) -> Option<Self> {
+        Some(Self {
+            x: i32::try_from(left).ok()?,
+            y: i32::try_from(top).ok()?,
+            width: u32::try_from(right.checked_sub(left)?).ok()?,
+            height: u32::try_from(bottom.checked_sub(top)?).ok()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    // Two u32s multiplied always fit in a u64, so the area can not overflow.
+    pub fn area(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+
+    // The area as an Option, so it chains with checked_add and friends when adding areas up.
+    pub fn checked_area(&self) -> Option<u64> {
+        u64::from(self.width).checked_mul(u64::from(self.height))
+    }
+
+    // Whether `other` would fit strictly inside, whatever the positions. Compare with contains below.
+    pub fn can_hold(&self, other: &Rectangle) -> bool {
+        self.width > other.width && self.height > other.height
+    }
+
+    // Like can_hold, but `other` may be exactly as wide or as tall. This is what packing needs.
+    pub fn fits(&self, other: &Rectangle) -> bool {
+        self.width >= other.width && self.height >= other.height
+    }
+
+    // The same rectangle turned 90° about its top left corner.
+    pub fn rotated(&self) -> Rectangle {
+        Rectangle::at(self.x, self.y, self.height, self.width)
+    }
+
+    pub fn contains_point(&self, x: i32, y: i32) -> bool {
+        let (x, y) = (i64::from(x), i64::from(y));
+        self.left() <= x && x < self.right() && self.top() <= y && y < self.bottom()
+    }
+
+    // Whether `other`, where it is, lies entirely inside this rectangle. Edges may touch.
+    pub fn contains(&self, other: &Rectangle) -> bool {
+        !other.is_empty()
+            && self.left() <= other.left()
+            && other.right() <= self.right()
+            && self.top() <= other.top()
+            && other.bottom() <= self.bottom()
+    }
+
+    pub fn intersects(&self, other: &Rectangle) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    // The area the two rectangles share, or None if they do not overlap or only touch.
+    pub fn intersection(&self, other: &Rectangle) -> Option<Rectangle> {
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if left >= right || top >= bottom {
+            return None;
+        }
+        Rectangle::from_edges(left, top, right, bottom)
+    }
+
+    // The smallest rectangle covering both, or None if it would be wider or taller than a u32.
+    // An empty rectangle covers nothing, so it does not stretch the result.
+    pub fn union(&self, other: &Rectangle) -> Option<Rectangle> {
+        if other.is_empty() {
+            return Some(*self);
+        }
+        if self.is_empty() {
+            return Some(*other);
+        }
+        Rectangle::from_edges(
+            self.left().min(other.left()),
+            self.top().min(other.top()),
+            self.right().max(other.right()),
+            self.bottom().max(other.bottom()),
+        )
+    }
+}
+
+impl fmt::Display for Rectangle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}x{} at ({}, {})",
+            self.width, self.height, self.x, self.y
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn area_does_not_overflow() {
+        let huge = Rectangle::new(u32::MAX, u32::MAX);
+        assert_eq!(huge.area(), u64::from(u32::MAX) * u64::from(u32::MAX));
+        assert_eq!(huge.checked_area(), Some(huge.area()));
+        assert_eq!(Rectangle::new(30, 50).checked_area(), Some(1500));
+        let total = huge
+            .checked_area()
+            .and_then(|area| area.checked_add(huge.area()))
+            .and_then(|area| area.checked_add(huge.area()));
+        assert_eq!(total, None);
+    }
+
+    #[test]
+    fn points_on_the_far_edges_are_outside() {
+        let rect = Rectangle::at(-5, 10, 10, 5);
+        assert!(rect.contains_point(-5, 10));
+        assert!(rect.contains_point(4, 14));
+        assert!(!rect.contains_point(5, 14));
+        assert!(!rect.contains_point(4, 15));
+        assert!(!Rectangle::at(0, 0, 0, 5).contains_point(0, 0));
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let a = Rectangle::at(0, 0, 10, 10);
+        let b = Rectangle::at(5, 5, 10, 10);
+        assert_eq!(a.intersection(&b), Some(Rectangle::at(5, 5, 5, 5)));
+        assert_eq!(a.union(&b), Some(Rectangle::at(0, 0, 15, 15)));
+        assert!(a.contains(&Rectangle::at(5, 5, 5, 5)));
+        assert!(!a.contains(&b));
+
+        // Touching along an edge is not overlapping.
+        let beside = Rectangle::at(10, 0, 10, 10);
+        assert!(!a.intersects(&beside));
+        assert_eq!(a.intersection(&beside), None);
+    }
+
+    #[test]
+    fn far_apart_rectangles_have_no_union() {
+        let left = Rectangle::at(i32::MIN, 0, 1, 1);
+        let right = Rectangle::at(i32::MAX, 0, u32::MAX, 1);
+        assert_eq!(left.union(&right), None);
+        assert!(!left.intersects(&right));
+        assert_eq!(left.union(&Rectangle::default()), Some(left));
+    }
+}
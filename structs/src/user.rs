@@ -0,0 +1,657 @@
+// User accounts: the User struct from src/main.rs grown into something a sign-up form can be pointed at.
+//
+// Usernames and emails are checked when they come in, and both are unique ignoring case, so "Ferris" and "ferris"
+// can not both sign up. A deactivated user keeps both, so nobody else can take them while the account is closed.
+
+pub mod builder; // UserBuilder, checking required fields when the user is built
+pub mod patch; // UserPatch, changing some fields of a user and reporting what changed
+
+pub use self::builder::{NewUser, UserBuilder};
+pub use self::patch::{Change, Field, UserPatch};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+pub type UserId = u64;
+
+pub const USERNAME_MIN: usize = 3;
+pub const USERNAME_MAX: usize = 32;
+// The limits on an address and its local part from RFC 5321.
+pub const EMAIL_MAX: usize = 254;
+pub const EMAIL_LOCAL_MAX: usize = 64;
+
+// Why a username was turned down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsernameError {
+    TooShort,
+    TooLong,
+    // Usernames start with a letter.
+    BadStart,
+    // Only ASCII letters, digits, '_' and '-' are allowed.
+    BadCharacter(char),
+}
+
+impl fmt::Display for UsernameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UsernameError::TooShort => write!(f, "at least {} characters", USERNAME_MIN),
+            UsernameError::TooLong => write!(f, "at most {} characters", USERNAME_MAX),
+            UsernameError::BadStart => write!(f, "it must start with a letter"),
+            UsernameError::BadCharacter(c) => write!(f, "{:?} is not allowed", c),
+        }
+    }
+}
+
+// Why an email address was turned down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmailError {
+    Empty,
+    TooLong,
+    MissingAt,
+    EmptyLocalPart,
+    LocalPartTooLong,
+    // The domain needs at least one dot, with only letters, digits and '-' between them.
+    BadDomain,
+    BadCharacter(char),
+}
+
+impl fmt::Display for EmailError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmailError::Empty => write!(f, "it is empty"),
+            EmailError::TooLong => write!(f, "at most {} characters", EMAIL_MAX),
+            EmailError::MissingAt => write!(f, "it needs exactly one '@'"),
+            EmailError::EmptyLocalPart => write!(f, "nothing before the '@'"),
+            EmailError::LocalPartTooLong => {
+                write!(f, "at most {} characters before the '@'", EMAIL_LOCAL_MAX)
+            }
+            EmailError::BadDomain => write!(f, "the part after the '@' is not a domain"),
+            EmailError::BadCharacter(c) => write!(f, "{:?} is not allowed", c),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserError {
+    InvalidUsername {
+        username: String,
+        reason: UsernameError,
+    },
+    InvalidEmail {
+        email: String,
+        reason: EmailError,
+    },
+    // A field UserBuilder needs was never set.
+    MissingField(&'static str),
+    SignInBeforeCreated,
+    // Sign ins are recorded in the order they happen, a later one can not be dated before the last.
+    SignInOutOfOrder,
+    UsernameTaken(String),
+    EmailTaken(String),
+    UnknownUser(UserId),
+    // Deactivated users can not sign in.
+    Deactivated(UserId),
+    AlreadyActive(UserId),
+    AlreadyDeactivated(UserId),
+}
+
+impl fmt::Display for UserError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UserError::InvalidUsername { username, reason } => {
+                write!(f, "username {:?} is not valid: {}", username, reason)
+            }
+            UserError::InvalidEmail { email, reason } => {
+                write!(f, "email {:?} is not valid: {}", email, reason)
+            }
+            UserError::MissingField(field) => write!(f, "{} is required", field),
+            UserError::SignInBeforeCreated => {
+                write!(f, "the last sign in is before the account was created")
+            }
+            UserError::SignInOutOfOrder => write!(f, "the sign in is before the last one"),
+            UserError::UsernameTaken(username) => write!(f, "username {} is taken", username),
+            UserError::EmailTaken(email) => write!(f, "{} already has an account", email),
+            UserError::UnknownUser(id) => write!(f, "there is no user {}", id),
+            UserError::Deactivated(id) => write!(f, "user {} is deactivated", id),
+            UserError::AlreadyActive(id) => write!(f, "user {} is already active", id),
+            UserError::AlreadyDeactivated(id) => write!(f, "user {} is already deactivated", id),
+        }
+    }
+}
+
+impl std::error::Error for UserError {}
+
+// A username that passed the rules. Compared as given, but unique in the store ignoring case.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Username(String);
+
+impl Username {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn key(&self) -> String {
+        self.0.to_ascii_lowercase()
+    }
+}
+
+impl FromStr for Username {
+    type Err = UserError;
+
+    fn from_str(text: &str) -> Result<Username, UserError> {
+        let invalid = |reason| UserError::InvalidUsername {
+            username: String::from(text),
+            reason,
+        };
+
+        let name = text.trim();
+        if let Some(c) = name
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+        {
+            return Err(invalid(UsernameError::BadCharacter(c)));
+        }
+        // All ASCII from here, so the length in bytes is the length in characters.
+        if name.len() < USERNAME_MIN {
+            return Err(invalid(UsernameError::TooShort));
+        }
+        if name.len() > USERNAME_MAX {
+            return Err(invalid(UsernameError::TooLong));
+        }
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return Err(invalid(UsernameError::BadStart));
+        }
+        Ok(Username(String::from(name)))
+    }
+}
+
+impl fmt::Display for Username {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&self.0)
+    }
+}
+
+// An email address that passed the rules, with the domain lowercased. Unique in the store ignoring case.
+//
+// This is the everyday shape of an address, not all of RFC 5322: quoted local parts and IP address domains are refused.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Email(String);
+
+impl Email {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn domain(&self) -> &str {
+        self.0.rsplit('@').next().unwrap_or_default()
+    }
+
+    fn key(&self) -> String {
+        self.0.to_lowercase()
+    }
+}
+
+impl FromStr for Email {
+    type Err = UserError;
+
+    fn from_str(text: &str) -> Result<Email, UserError> {
+        let invalid = |reason| UserError::InvalidEmail {
+            email: String::from(text),
+            reason,
+        };
+
+        let address = text.trim();
+        if address.is_empty() {
+            return Err(invalid(EmailError::Empty));
+        }
+        if address.chars().count() > EMAIL_MAX {
+            return Err(invalid(EmailError::TooLong));
+        }
+        let Some((local, domain)) = address.split_once('@') else {
+            return Err(invalid(EmailError::MissingAt));
+        };
+        if domain.contains('@') {
+            return Err(invalid(EmailError::MissingAt));
+        }
+
+        if local.is_empty() {
+            return Err(invalid(EmailError::EmptyLocalPart));
+        }
+        if local.chars().count() > EMAIL_LOCAL_MAX {
+            return Err(invalid(EmailError::LocalPartTooLong));
+        }
+        let allowed_in_local =
+            |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~.".contains(c);
+        if let Some(c) = local.chars().find(|&c| !allowed_in_local(c)) {
+            return Err(invalid(EmailError::BadCharacter(c)));
+        }
+        if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+            return Err(invalid(EmailError::BadCharacter('.')));
+        }
+
+        let labels: Vec<&str> = domain.split('.').collect();
+        let good_label = |label: &&str| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        };
+        if labels.len() < 2 || !labels.iter().all(good_label) {
+            return Err(invalid(EmailError::BadDomain));
+        }
+
+        Ok(Email(format!("{}@{}", local, domain.to_ascii_lowercase())))
+    }
+}
+
+impl fmt::Display for Email {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    id: UserId,
+    username: Username,
+    email: Email,
+    active: bool,
+    sign_in_count: u64,
+    created_at: SystemTime,
+    last_sign_in: Option<SystemTime>,
+}
+
+impl User {
+    pub fn id(&self) -> UserId {
+        self.id
+    }
+
+    pub fn username(&self) -> &Username {
+        &self.username
+    }
+
+    pub fn email(&self) -> &Email {
+        &self.email
+    }
+
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    pub fn sign_in_count(&self) -> u64 {
+        self.sign_in_count
+    }
+
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
+
+    pub fn last_sign_in(&self) -> Option<SystemTime> {
+        self.last_sign_in
+    }
+}
+
+// All the users, with indexes so usernames and emails stay unique.
+#[derive(Debug, Default)]
+pub struct UserStore {
+    users: BTreeMap<UserId, User>,
+    by_username: HashMap<String, UserId>,
+    by_email: HashMap<String, UserId>,
+    next_id: UserId,
+}
+
+impl UserStore {
+    pub fn new() -> UserStore {
+        UserStore {
+            next_id: 1,
+            ..UserStore::default()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+
+    pub fn get(&self, id: UserId) -> Result<&User, UserError> {
+        self.users.get(&id).ok_or(UserError::UnknownUser(id))
+    }
+
+    // In the order they signed up.
+    pub fn iter(&self) -> impl Iterator<Item = &User> {
+        self.users.values()
+    }
+
+    // Ignores case, like the uniqueness check.
+    pub fn find_by_username(&self, username: &str) -> Option<&User> {
+        let id = self
+            .by_username
+            .get(&username.trim().to_ascii_lowercase())?;
+        self.users.get(id)
+    }
+
+    pub fn find_by_email(&self, email: &str) -> Option<&User> {
+        let id = self.by_email.get(&email.trim().to_lowercase())?;
+        self.users.get(id)
+    }
+
+    // A new, active user who has not signed in yet.
+    pub fn create(
+        &mut self,
+        username: &str,
+        email: &str,
+        now: SystemTime,
+    ) -> Result<&User, UserError> {
+        let user = UserBuilder::new()
+            .username(username)
+            .email(email)
+            .created_at(now)
+            .build()?;
+        self.add(user)
+    }
+
+    pub fn add(&mut self, user: NewUser) -> Result<&User, UserError> {
+        self.check_username_free(&user.username, None)?;
+        self.check_email_free(&user.email, None)?;
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.by_username.insert(user.username.key(), id);
+        self.by_email.insert(user.email.key(), id);
+        let user = User {
+            id,
+            username: user.username,
+            email: user.email,
+            active: user.active,
+            sign_in_count: user.sign_in_count,
+            created_at: user.created_at,
+            last_sign_in: user.last_sign_in,
+        };
+        Ok(self.users.entry(id).or_insert(user))
+    }
+
+    // Applies the whole patch or, if any part of it is refused, none of it. Returns what changed.
+    pub fn apply(&mut self, id: UserId, patch: &UserPatch) -> Result<Vec<Change>, UserError> {
+        let (username, email) = patch.parsed()?;
+        let changes = patch.changes(self.get(id)?, username.as_ref(), email.as_ref());
+        if let Some(username) = &username {
+            self.check_username_free(username, Some(id))?;
+        }
+        if let Some(email) = &email {
+            self.check_email_free(email, Some(id))?;
+        }
+
+        let user = self.users.get_mut(&id).expect("checked above");
+        if let Some(username) = username {
+            self.by_username.remove(&user.username.key());
+            self.by_username.insert(username.key(), id);
+            user.username = username;
+        }
+        if let Some(email) = email {
+            self.by_email.remove(&user.email.key());
+            self.by_email.insert(email.key(), id);
+            user.email = email;
+        }
+        if let Some(active) = patch.active_flag() {
+            user.active = active;
+        }
+        Ok(changes)
+    }
+
+    pub fn change_username(&mut self, id: UserId, username: &str) -> Result<&User, UserError> {
+        self.apply(id, &UserPatch::new().username(username))?;
+        self.get(id)
+    }
+
+    pub fn change_email(&mut self, id: UserId, email: &str) -> Result<&User, UserError> {
+        self.apply(id, &UserPatch::new().email(email))?;
+        self.get(id)
+    }
+
+    pub fn record_sign_in(&mut self, id: UserId, now: SystemTime) -> Result<&User, UserError> {
+        let user = self.user_mut(id)?;
+        if !user.active {
+            return Err(UserError::Deactivated(id));
+        }
+        if now < user.created_at {
+            return Err(UserError::SignInBeforeCreated);
+        }
+        if user.last_sign_in.is_some_and(|last| now < last) {
+            return Err(UserError::SignInOutOfOrder);
+        }
+        user.sign_in_count += 1;
+        user.last_sign_in = Some(now);
+        Ok(user)
+    }
+
+    pub fn deactivate(&mut self, id: UserId) -> Result<&User, UserError> {
+        let user = self.user_mut(id)?;
+        if !user.active {
+            return Err(UserError::AlreadyDeactivated(id));
+        }
+        user.active = false;
+        Ok(user)
+    }
+
+    pub fn reactivate(&mut self, id: UserId) -> Result<&User, UserError> {
+        let user = self.user_mut(id)?;
+        if user.active {
+            return Err(UserError::AlreadyActive(id));
+        }
+        user.active = true;
+        Ok(user)
+    }
+
+    fn user_mut(&mut self, id: UserId) -> Result<&mut User, UserError> {
+        self.users.get_mut(&id).ok_or(UserError::UnknownUser(id))
+    }
+
+    // Taken by anyone but `owner`, who may keep their own username with different case.
+    fn check_username_free(
+        &self,
+        username: &Username,
+        owner: Option<UserId>,
+    ) -> Result<(), UserError> {
+        match self.by_username.get(&username.key()) {
+            Some(&id) if Some(id) != owner => Err(UserError::UsernameTaken(username.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_email_free(&self, email: &Email, owner: Option<UserId>) -> Result<(), UserError> {
+        match self.by_email.get(&email.key()) {
+            Some(&id) if Some(id) != owner => Err(UserError::EmailTaken(email.to_string())),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn checks_usernames_and_emails() {
+        assert_eq!(
+            "  ferris_42 ".parse(),
+            Ok(Username(String::from("ferris_42")))
+        );
+        let reason = |text: &str| match text.parse::<Username>() {
+            Err(UserError::InvalidUsername { reason, .. }) => Some(reason),
+            _ => None,
+        };
+        assert_eq!(reason("fe"), Some(UsernameError::TooShort));
+        assert_eq!(reason(&"f".repeat(33)), Some(UsernameError::TooLong));
+        assert_eq!(reason("42ferris"), Some(UsernameError::BadStart));
+        assert_eq!(reason("fer ris"), Some(UsernameError::BadCharacter(' ')));
+        assert_eq!(reason("férris"), Some(UsernameError::BadCharacter('é')));
+
+        assert_eq!(
+            "Ferris.Crab+rust@Example.COM"
+                .parse::<Email>()
+                .map(|e| e.to_string()),
+            Ok(String::from("Ferris.Crab+rust@example.com"))
+        );
+        let reason = |text: &str| match text.parse::<Email>() {
+            Err(UserError::InvalidEmail { reason, .. }) => Some(reason),
+            _ => None,
+        };
+        assert_eq!(reason(""), Some(EmailError::Empty));
+        assert_eq!(reason("ferris.example.com"), Some(EmailError::MissingAt));
+        assert_eq!(reason("a@b@example.com"), Some(EmailError::MissingAt));
+        assert_eq!(reason("@example.com"), Some(EmailError::EmptyLocalPart));
+        assert_eq!(reason("ferris@localhost"), Some(EmailError::BadDomain));
+        assert_eq!(reason("ferris@-rust.org"), Some(EmailError::BadDomain));
+        assert_eq!(
+            reason("fer..ris@rust.org"),
+            Some(EmailError::BadCharacter('.'))
+        );
+        assert_eq!(
+            reason("fer ris@rust.org"),
+            Some(EmailError::BadCharacter(' '))
+        );
+        assert_eq!(
+            reason(&format!("{}@rust.org", "f".repeat(65))),
+            Some(EmailError::LocalPartTooLong)
+        );
+    }
+
+    #[test]
+    fn usernames_and_emails_are_unique_ignoring_case() {
+        let mut store = UserStore::new();
+        let ferris = store
+            .create("Ferris", "ferris@rust.org", at(0))
+            .unwrap()
+            .id();
+        assert_eq!(
+            store.create("ferris", "other@rust.org", at(1)),
+            Err(UserError::UsernameTaken(String::from("ferris")))
+        );
+        assert_eq!(
+            store.create("corro", "FERRIS@rust.org", at(1)),
+            Err(UserError::EmailTaken(String::from("FERRIS@rust.org")))
+        );
+        assert_eq!(store.len(), 1);
+
+        // Changing the case of your own username is fine, and frees nothing up.
+        store.change_username(ferris, "FERRIS").unwrap();
+        assert_eq!(
+            store
+                .find_by_username("ferris")
+                .unwrap()
+                .username()
+                .as_str(),
+            "FERRIS"
+        );
+        let corro = store.create("corro", "corro@rust.org", at(2)).unwrap().id();
+        assert_eq!(
+            store.change_email(corro, "Ferris@Rust.org"),
+            Err(UserError::EmailTaken(String::from("Ferris@rust.org")))
+        );
+        store.change_email(ferris, "crab@rust.org").unwrap();
+        store.change_email(corro, "ferris@rust.org").unwrap();
+        assert_eq!(store.find_by_email("FERRIS@rust.org").unwrap().id(), corro);
+    }
+
+    #[test]
+    fn patches_apply_whole_or_not_at_all() {
+        let mut store = UserStore::new();
+        let ferris = store
+            .create("ferris", "ferris@rust.org", at(0))
+            .unwrap()
+            .id();
+        store.create("corro", "corro@rust.org", at(0)).unwrap();
+        let before = store.get(ferris).unwrap().clone();
+
+        // The username is free but the email is not, so neither changes.
+        let patch = UserPatch::new().username("crab").email("CORRO@rust.org");
+        assert!(matches!(
+            store.apply(ferris, &patch),
+            Err(UserError::EmailTaken(_))
+        ));
+        assert_eq!(store.get(ferris), Ok(&before));
+
+        let patch = UserPatch::new()
+            .email("crab@Rust.org")
+            .active(false)
+            .username("ferris");
+        let changes = store.apply(ferris, &patch).unwrap();
+        let log: Vec<String> = changes.iter().map(|change| change.to_string()).collect();
+        assert_eq!(
+            log,
+            [
+                "email: ferris@rust.org -> crab@rust.org",
+                "active: true -> false"
+            ]
+        );
+        assert!(store.find_by_email("ferris@rust.org").is_none());
+
+        let after = store.get(ferris).unwrap();
+        assert_eq!(
+            UserPatch::between(&before, after),
+            UserPatch::new().email("crab@rust.org").active(false)
+        );
+        assert_eq!(UserPatch::between(after, after).diff(after), Ok(vec![]));
+        assert!(UserPatch::between(after, after).is_empty());
+    }
+
+    #[test]
+    fn sign_ins_are_counted_while_active() {
+        let mut store = UserStore::new();
+        let id = store
+            .create("ferris", "ferris@rust.org", at(0))
+            .unwrap()
+            .id();
+        store.record_sign_in(id, at(60)).unwrap();
+        let user = store.record_sign_in(id, at(120)).unwrap();
+        assert_eq!(user.sign_in_count(), 2);
+        assert_eq!(user.last_sign_in(), Some(at(120)));
+        assert_eq!(
+            store.record_sign_in(id, at(90)),
+            Err(UserError::SignInOutOfOrder)
+        );
+        assert_eq!(
+            store.record_sign_in(id, at(120)).unwrap().sign_in_count(),
+            3
+        );
+
+        store.deactivate(id).unwrap();
+        assert_eq!(
+            store.record_sign_in(id, at(180)),
+            Err(UserError::Deactivated(id))
+        );
+        assert_eq!(store.deactivate(id), Err(UserError::AlreadyDeactivated(id)));
+        // The name stays taken while the account is closed.
+        assert!(matches!(
+            store.create("Ferris", "new@rust.org", at(200)),
+            Err(UserError::UsernameTaken(_))
+        ));
+
+        store.reactivate(id).unwrap();
+        assert_eq!(store.reactivate(id), Err(UserError::AlreadyActive(id)));
+        assert_eq!(
+            store.record_sign_in(id, at(240)).unwrap().sign_in_count(),
+            4
+        );
+        assert_eq!(
+            store.record_sign_in(99, at(240)),
+            Err(UserError::UnknownUser(99))
+        );
+
+        let late = store.create("crab", "crab@rust.org", at(300)).unwrap().id();
+        assert_eq!(
+            store.record_sign_in(late, at(299)),
+            Err(UserError::SignInBeforeCreated)
+        );
+    }
+}
@@ -0,0 +1,165 @@
+// Building a user field by field, instead of a struct literal or ..user1 struct update syntax.
+//
+// Nothing is checked until build, which reports the first field that is missing or invalid.
+// Uniqueness needs the other users, so that is left to UserStore::add.
+
+use std::time::SystemTime;
+
+use super::{Email, UserError, Username};
+
+#[derive(Debug, Clone, Default)]
+pub struct UserBuilder {
+    username: Option<String>,
+    email: Option<String>,
+    created_at: Option<SystemTime>,
+    active: Option<bool>,
+    sign_ins: Option<(u64, SystemTime)>,
+}
+
+impl UserBuilder {
+    pub fn new() -> UserBuilder {
+        UserBuilder::default()
+    }
+
+    // Required.
+    pub fn username(mut self, username: &str) -> UserBuilder {
+        self.username = Some(String::from(username));
+        self
+    }
+
+    // Required.
+    pub fn email(mut self, email: &str) -> UserBuilder {
+        self.email = Some(String::from(email));
+        self
+    }
+
+    // Required.
+    pub fn created_at(mut self, created_at: SystemTime) -> UserBuilder {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    // Optional, users are active unless told otherwise.
+    pub fn active(mut self, active: bool) -> UserBuilder {
+        self.active = Some(active);
+        self
+    }
+
+    // Optional, for accounts brought over from elsewhere. A count of 0 means never signed in, whatever `last` says.
+    pub fn signed_in(mut self, count: u64, last: SystemTime) -> UserBuilder {
+        self.sign_ins = Some((count, last));
+        self
+    }
+
+    pub fn build(self) -> Result<NewUser, UserError> {
+        let username: Username = self
+            .username
+            .ok_or(UserError::MissingField("username"))?
+            .parse()?;
+        let email: Email = self
+            .email
+            .ok_or(UserError::MissingField("email"))?
+            .parse()?;
+        let created_at = self
+            .created_at
+            .ok_or(UserError::MissingField("created_at"))?;
+
+        let (sign_in_count, last_sign_in) = match self.sign_ins {
+            Some((count, last)) if count > 0 => {
+                if last < created_at {
+                    return Err(UserError::SignInBeforeCreated);
+                }
+                (count, Some(last))
+            }
+            _ => (0, None),
+        };
+
+        Ok(NewUser {
+            username,
+            email,
+            active: self.active.unwrap_or(true),
+            sign_in_count,
+            created_at,
+            last_sign_in,
+        })
+    }
+}
+
+// A user that passed every check but uniqueness, waiting for UserStore::add to give it an id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewUser {
+    pub(super) username: Username,
+    pub(super) email: Email,
+    pub(super) active: bool,
+    pub(super) sign_in_count: u64,
+    pub(super) created_at: SystemTime,
+    pub(super) last_sign_in: Option<SystemTime>,
+}
+
+impl NewUser {
+    pub fn username(&self) -> &Username {
+        &self.username
+    }
+
+    pub fn email(&self) -> &Email {
+        &self.email
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn required_fields_are_checked_at_build() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        let builder = UserBuilder::new().username("ferris").created_at(now);
+        assert_eq!(
+            builder.clone().build(),
+            Err(UserError::MissingField("email"))
+        );
+
+        let user = builder.clone().email("ferris@rust.org").build().unwrap();
+        assert!(user.active);
+        assert_eq!(user.sign_in_count, 0);
+
+        assert!(matches!(
+            builder.clone().email("ferris").build(),
+            Err(UserError::InvalidEmail { .. })
+        ));
+        assert_eq!(
+            UserBuilder::new().email("ferris@rust.org").build(),
+            Err(UserError::MissingField("username"))
+        );
+    }
+
+    #[test]
+    fn imported_sign_ins_come_after_creation() {
+        let created = UNIX_EPOCH + Duration::from_secs(1_000);
+        let builder = UserBuilder::new()
+            .username("ferris")
+            .email("ferris@rust.org")
+            .created_at(created)
+            .active(false);
+
+        let user = builder
+            .clone()
+            .signed_in(7, created + Duration::from_secs(60))
+            .build()
+            .unwrap();
+        assert_eq!((user.sign_in_count, user.active), (7, false));
+        assert_eq!(
+            builder.clone().signed_in(7, UNIX_EPOCH).build(),
+            Err(UserError::SignInBeforeCreated)
+        );
+        assert_eq!(
+            builder
+                .signed_in(0, UNIX_EPOCH)
+                .build()
+                .unwrap()
+                .last_sign_in,
+            None
+        );
+    }
+}
@@ -0,0 +1,189 @@
+// A partial update to a user: only the fields that are set change. Applied with UserStore::apply,
+// which returns the changes it made for the change log.
+
+use std::fmt;
+
+use super::{Email, User, UserError, Username};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Field {
+    Username,
+    Email,
+    Active,
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Field::Username => "username",
+            Field::Email => "email",
+            Field::Active => "active",
+        };
+        f.pad(name)
+    }
+}
+
+// One line of a change log: a field and its value before and after.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub field: Field,
+    pub from: String,
+    pub to: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.from, self.to)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserPatch {
+    username: Option<String>,
+    email: Option<String>,
+    active: Option<bool>,
+}
+
+impl UserPatch {
+    pub fn new() -> UserPatch {
+        UserPatch::default()
+    }
+
+    pub fn username(mut self, username: &str) -> UserPatch {
+        self.username = Some(String::from(username));
+        self
+    }
+
+    pub fn email(mut self, email: &str) -> UserPatch {
+        self.email = Some(String::from(email));
+        self
+    }
+
+    pub fn active(mut self, active: bool) -> UserPatch {
+        self.active = Some(active);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == UserPatch::default()
+    }
+
+    // The patch that turns `before` into `after`, leaving out what they agree on.
+    pub fn between(before: &User, after: &User) -> UserPatch {
+        UserPatch {
+            username: (before.username != after.username).then(|| after.username.to_string()),
+            email: (before.email != after.email).then(|| after.email.to_string()),
+            active: (before.active != after.active).then_some(after.active),
+        }
+    }
+
+    // The new username and email checked against the rules. Fields that are not set stay None.
+    pub(super) fn parsed(&self) -> Result<(Option<Username>, Option<Email>), UserError> {
+        let username = self.username.as_deref().map(str::parse).transpose()?;
+        let email = self.email.as_deref().map(str::parse).transpose()?;
+        Ok((username, email))
+    }
+
+    pub(super) fn active_flag(&self) -> Option<bool> {
+        self.active
+    }
+
+    // What applying the patch to `user` would change. Setting a field to the value it already has is not a change.
+    pub fn diff(&self, user: &User) -> Result<Vec<Change>, UserError> {
+        let (username, email) = self.parsed()?;
+        Ok(self.changes(user, username.as_ref(), email.as_ref()))
+    }
+
+    // diff for a patch whose username and email have already been parsed.
+    pub(super) fn changes(
+        &self,
+        user: &User,
+        username: Option<&Username>,
+        email: Option<&Email>,
+    ) -> Vec<Change> {
+        let mut changes = Vec::new();
+        if let Some(username) = username.filter(|&username| *username != user.username) {
+            changes.push(Change {
+                field: Field::Username,
+                from: user.username.to_string(),
+                to: username.to_string(),
+            });
+        }
+        if let Some(email) = email.filter(|&email| *email != user.email) {
+            changes.push(Change {
+                field: Field::Email,
+                from: user.email.to_string(),
+                to: email.to_string(),
+            });
+        }
+        if let Some(active) = self.active.filter(|&active| active != user.active) {
+            changes.push(Change {
+                field: Field::Active,
+                from: user.active.to_string(),
+                to: active.to_string(),
+            });
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::{EmailError, UserStore};
+    use std::time::UNIX_EPOCH;
+
+    fn ferris() -> (UserStore, User) {
+        let mut store = UserStore::new();
+        let user = store
+            .create("ferris", "ferris@rust.org", UNIX_EPOCH)
+            .unwrap()
+            .clone();
+        (store, user)
+    }
+
+    #[test]
+    fn setting_what_is_already_there_changes_nothing() {
+        let (_, user) = ferris();
+        assert!(UserPatch::new().is_empty());
+        assert_eq!(UserPatch::new().diff(&user), Ok(vec![]));
+
+        let same = UserPatch::new()
+            .username("ferris")
+            .email("ferris@rust.org")
+            .active(true);
+        assert!(!same.is_empty());
+        assert_eq!(same.diff(&user), Ok(vec![]));
+        assert!(UserPatch::between(&user, &user).is_empty());
+    }
+
+    #[test]
+    fn invalid_values_are_refused_before_diffing() {
+        let (_, user) = ferris();
+        assert!(matches!(
+            UserPatch::new().email("no-at-sign").diff(&user),
+            Err(UserError::InvalidEmail {
+                reason: EmailError::MissingAt,
+                ..
+            })
+        ));
+        assert!(matches!(
+            UserPatch::new().username("").diff(&user),
+            Err(UserError::InvalidUsername { .. })
+        ));
+    }
+
+    #[test]
+    fn a_change_of_case_is_a_change() {
+        let (mut store, user) = ferris();
+        let patch = UserPatch::new().username("Ferris");
+        let changes = patch.diff(&user).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].to_string(), "username: ferris -> Ferris");
+
+        // The name is taken ignoring case, but only by this user, so the store lets it through.
+        assert_eq!(store.apply(user.id(), &patch), Ok(changes));
+        let after = store.get(user.id()).unwrap();
+        assert_eq!(UserPatch::between(&user, after), patch);
+    }
+}
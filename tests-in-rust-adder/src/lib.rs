@@ -15,17 +15,7 @@ fn prints_and_returns_10(a: i32) -> i32 {
     10
 }
  
-#[derive(Debug)]
-struct Rectangle {
-    width: u32,
-    height: u32,
-}
-
-impl Rectangle {
-    fn can_hold(&self, other: &Rectangle) -> bool {
-        self.width > other.width && self.height > other.height
-    }
-}
+pub use structs::Rectangle; // The same Rectangle as the structs crate, can_hold included.
 // The below code is an example of tests in Rust.
 #[cfg(test)]
 mod tests {
@@ -57,15 +47,9 @@ mod tests {
 
     #[test]
     fn larger_can_hold_smaller() {
-        let larger = Rectangle {
-            width: 8,
-            height: 7,
-        };
+        let larger = Rectangle::new(8, 7);
 
-        let smaller = Rectangle {
-            width: 5,
-            height: 1,
-        };
+        let smaller = Rectangle::new(5, 1);
 
         // assert is basically used to as an assertion, basically if an assert returns true, it passes, if its the opposite it fails, it basically used
         // to define assertions in Rust.
@@ -74,15 +58,9 @@ mod tests {
 
     #[test]
     fn smaller_cannot_hold_larger() {
-        let larger = Rectangle {
-            width: 8,
-            height: 7,
-        };
-
-        let smaller = Rectangle {
-            width: 5,
-            height: 1,
-        };
+        let larger = Rectangle::new(8, 7);
+
+        let smaller = Rectangle::new(5, 1);
 
         // Here we need to negate the result as the original result is false.
         assert!(!smaller.can_hold(&larger));
@@ -1,4 +1,4 @@
-use tests_in_rust_adder::add;
+use tests_in_rust_adder::{add, Rectangle};
 
 #[test]
 fn it_adds_two() {
@@ -6,5 +6,13 @@ fn it_adds_two() {
     assert_eq!(result, 4);
 }
 
+#[test]
+fn overlapping_rectangles() {
+    let a = Rectangle::at(0, 0, 4, 4);
+    let b = Rectangle::at(2, 2, 4, 4);
+    assert_eq!(a.intersection(&b), Some(Rectangle::at(2, 2, 2, 2)));
+    assert_eq!(a.union(&b).map(|r| r.area()), Some(36));
+}
+
 // There are three sections of output: the unit tests, the integration tests and the doc tests.
 // If any test in a section fails, the following sections will not run.
\ No newline at end of file